@@ -0,0 +1,14 @@
+//! feeds arbitrary bytes to the parser of a day, the first byte selecting the day
+//!
+//! `cargo +nightly fuzz run parse_inputs`
+#![no_main]
+
+use aoc2022::{DAYS, LAST_DAY};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((day, input)) = data.split_first() {
+        let input = String::from_utf8_lossy(input);
+        let _ = (DAYS[*day as usize % LAST_DAY].check)(&input);
+    }
+});
@@ -0,0 +1,324 @@
+use crate::verify::DEFAULT_ANSWERS_FILE;
+use aoc2022::day7::Listing;
+use aoc2022::input::InputSource;
+use aoc2022::solution::Parts;
+use aoc2022::LAST_DAY;
+use eyre::{eyre, Context};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+usage: aoc2022 [verify | check | stats | tree | du] [DAYS]... [--part 1|2|both] [--input FILE | --resources DIR] [--report FILE] [--parallel] [--top N] [--step N] [--by-size] [--depth N]
+
+  verify           compare the answers with the expected ones instead of printing them
+  check            only report the structural problems of each input, with their line numbers
+  stats            print statistics about the elves inventory of day 1, instead of solving it
+  tree, du         print the day 7 file system like tree or du -a, then the directories each part chose
+  DAYS             day numbers, ranges (15..=19, 15..20) or comma separated lists of them
+                   all days are run when none is given
+  -p, --part       only run the first or the second part of each day (default : both)
+  -i, --input      read the input of the (single) selected day from FILE, `-` being stdin
+  -r, --resources  read each day input from DIR/dayN_*.txt (default : resources)
+                   the input embedded at build time is used when there is no such file
+      --report     also write answers and timings (in µs) to FILE, as .json or .csv
+                   or the statistics of stats, as .json
+      --answers    expected answers used by verify (default : resources/answers.toml)
+      --top        number of most charged elves listed by stats (default : 3)
+      --step       print the day 5 crate stacks after N moves, instead of solving it
+      --by-size    list the largest entries first in tree and du
+      --depth      leave out the entries deeper than N in tree and du, the root being at depth 0
+  -j, --parallel   solve the selected days concurrently, answers being still printed in day order
+  -h, --help       print this help";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Mode {
+    Run,
+    Verify { answers: PathBuf },
+    Check,
+    Stats { top: usize },
+    Step { step: usize },
+    Tree { listing: Listing },
+    Du { listing: Listing },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Args {
+    pub mode: Mode,
+    pub days: Vec<usize>,
+    pub parts: Parts,
+    pub input: InputSource,
+    pub report: Option<PathBuf>,
+    pub parallel: bool,
+}
+
+/// selects `day`, failing when others were selected
+fn only_day(days: &mut BTreeSet<usize>, day: usize, what: &str) -> eyre::Result<()> {
+    if days.iter().any(|d| *d != day) {
+        return Err(eyre!("{what} only available for day {day}"));
+    }
+    days.insert(day);
+    Ok(())
+}
+
+fn parse_day(s: &str) -> eyre::Result<usize> {
+    let day: usize = s
+        .trim()
+        .parse()
+        .with_context(|| format!("{s} is not a day number"))?;
+    if !(1..=LAST_DAY).contains(&day) {
+        return Err(eyre!("day {day} is not in 1..={LAST_DAY}"));
+    }
+    Ok(day)
+}
+
+/// reads `7`, `15..=19` or `15..20`, a range selecting at least one day
+fn parse_days(s: &str) -> eyre::Result<Vec<usize>> {
+    let (start, end) = if let Some((start, end)) = s.split_once("..=") {
+        (parse_day(start)?, parse_day(end)?)
+    } else if let Some((start, end)) = s.split_once("..") {
+        let end: usize = end
+            .trim()
+            .parse()
+            .with_context(|| format!("{end} is not a day number"))?;
+        if !(2..=LAST_DAY + 1).contains(&end) {
+            return Err(eyre!("exclusive end {end} is not in 2..={}", LAST_DAY + 1));
+        }
+        (parse_day(start)?, end - 1)
+    } else {
+        let day = parse_day(s)?;
+        (day, day)
+    };
+    if start > end {
+        return Err(eyre!("{s} selects no day"));
+    }
+    Ok((start..=end).collect())
+}
+
+impl Args {
+    /// `help` is not handled here, see [USAGE]
+    pub fn parse(args: impl IntoIterator<Item = String>) -> eyre::Result<Self> {
+        let mut days = BTreeSet::new();
+        let mut parts = Parts::Both;
+        let mut input = InputSource::default();
+        let mut report = None;
+        let mut verify = false;
+        let mut check = false;
+        let mut stats = false;
+        let mut top = 3;
+        let mut step = None;
+        let mut tree = false;
+        let mut du = false;
+        let mut listing = Listing::default();
+        let mut parallel = false;
+        let mut answers = PathBuf::from(DEFAULT_ANSWERS_FILE);
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-p" | "--part" => {
+                    let value = args.next().ok_or_else(|| eyre!("{arg} expects a value"))?;
+                    parts = value.parse()?;
+                }
+                "-i" | "--input" => {
+                    let value = args.next().ok_or_else(|| eyre!("{arg} expects a value"))?;
+                    input = InputSource::from_arg(&value);
+                }
+                "-r" | "--resources" => {
+                    let value = args.next().ok_or_else(|| eyre!("{arg} expects a value"))?;
+                    input = InputSource::Resources(PathBuf::from(value));
+                }
+                "--report" => {
+                    let value = args.next().ok_or_else(|| eyre!("{arg} expects a value"))?;
+                    report = Some(PathBuf::from(value));
+                }
+                "--answers" => {
+                    let value = args.next().ok_or_else(|| eyre!("{arg} expects a value"))?;
+                    answers = PathBuf::from(value);
+                }
+                "--top" => {
+                    let value = args.next().ok_or_else(|| eyre!("{arg} expects a value"))?;
+                    top = value
+                        .parse()
+                        .with_context(|| format!("{value} is not a number of elves"))?;
+                }
+                "--step" => {
+                    let value = args.next().ok_or_else(|| eyre!("{arg} expects a value"))?;
+                    step = Some(
+                        value
+                            .parse()
+                            .with_context(|| format!("{value} is not a number of moves"))?,
+                    );
+                }
+                "--depth" => {
+                    let value = args.next().ok_or_else(|| eyre!("{arg} expects a value"))?;
+                    listing.max_depth = Some(
+                        value
+                            .parse()
+                            .with_context(|| format!("{value} is not a depth"))?,
+                    );
+                }
+                "--by-size" => listing.by_size = true,
+                "-j" | "--parallel" => parallel = true,
+                "verify" => verify = true,
+                "check" => check = true,
+                "stats" => stats = true,
+                "tree" => tree = true,
+                "du" => du = true,
+                _ if arg.starts_with('-') => return Err(eyre!("unknown option {arg}")),
+                _ => {
+                    for item in arg.split(',').filter(|i| !i.is_empty()) {
+                        days.extend(parse_days(item)?);
+                    }
+                }
+            }
+        }
+        if [verify, check, stats, step.is_some(), tree, du]
+            .into_iter()
+            .filter(|m| *m)
+            .count()
+            > 1
+        {
+            return Err(eyre!(
+                "verify, check, stats, tree, du and --step cannot be combined"
+            ));
+        }
+        if stats {
+            only_day(&mut days, 1, "stats are")?;
+        }
+        if step.is_some() {
+            only_day(&mut days, 5, "steps are")?;
+        }
+        if tree || du {
+            only_day(&mut days, 7, "listings are")?;
+        }
+        if days.is_empty() {
+            days.extend(1..=LAST_DAY);
+        }
+        if days.len() > 1 && !matches!(input, InputSource::Resources(_)) {
+            return Err(eyre!("an input file can only be given for a single day"));
+        }
+
+        Ok(Self {
+            mode: if verify {
+                Mode::Verify { answers }
+            } else if check {
+                Mode::Check
+            } else if stats {
+                Mode::Stats { top }
+            } else if let Some(step) = step {
+                Mode::Step { step }
+            } else if tree {
+                Mode::Tree { listing }
+            } else if du {
+                Mode::Du { listing }
+            } else {
+                Mode::Run
+            },
+            days: days.into_iter().collect(),
+            parts,
+            input,
+            report,
+            parallel,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> eyre::Result<Args> {
+        Args::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn selection_works() {
+        assert_eq!(
+            Args {
+                mode: Mode::Run,
+                days: (1..=25).collect(),
+                parts: Parts::Both,
+                input: InputSource::default(),
+                report: None,
+                parallel: false,
+            },
+            parse(&[]).unwrap()
+        );
+        assert_eq!(
+            Args {
+                mode: Mode::Run,
+                days: vec![7],
+                parts: Parts::Second,
+                input: InputSource::Stdin,
+                report: Some(PathBuf::from("bench.csv")),
+                parallel: false,
+            },
+            parse(&["7", "--part", "2", "-i", "-", "--report", "bench.csv"]).unwrap()
+        );
+        assert_eq!(
+            InputSource::File(PathBuf::from("mine.txt")),
+            parse(&["7", "--input", "mine.txt"]).unwrap().input
+        );
+        assert_eq!(
+            InputSource::Resources(PathBuf::from("other")),
+            parse(&["-r", "other"]).unwrap().input
+        );
+        assert_eq!(
+            vec![1, 3, 15, 16, 17, 18, 19],
+            parse(&["15..=19", "3,1", "17..19"]).unwrap().days
+        );
+        assert_eq!(vec![24, 25], parse(&["24..26"]).unwrap().days);
+        assert!(parse(&["24..30"]).is_err());
+        assert!(parse(&["3..=1"]).is_err());
+        assert!(parse(&["5..3"]).is_err());
+        assert!(parse(&["5..5"]).is_err());
+        assert!(parse(&["1..1"]).is_err());
+        assert!(parse(&["-j"]).unwrap().parallel);
+        assert_eq!(
+            Mode::Verify {
+                answers: PathBuf::from(DEFAULT_ANSWERS_FILE)
+            },
+            parse(&["verify", "1..=3"]).unwrap().mode
+        );
+        assert_eq!(
+            Mode::Verify {
+                answers: PathBuf::from("mine.toml")
+            },
+            parse(&["verify", "--answers", "mine.toml"]).unwrap().mode
+        );
+
+        assert_eq!(
+            Mode::Check,
+            parse(&["check", "7", "-i", "mine.txt"]).unwrap().mode
+        );
+        assert!(parse(&["verify", "check"]).is_err());
+        let stats = parse(&["stats", "--top", "5", "-i", "mine.txt"]).unwrap();
+        assert_eq!((Mode::Stats { top: 5 }, vec![1]), (stats.mode, stats.days));
+        assert!(parse(&["stats", "2"]).is_err());
+        let step = parse(&["--step", "3", "-p", "2"]).unwrap();
+        assert_eq!((Mode::Step { step: 3 }, vec![5]), (step.mode, step.days));
+        assert!(parse(&["--step", "3", "6"]).is_err());
+        assert!(parse(&["--step", "3", "check"]).is_err());
+        let tree = parse(&["tree", "--by-size", "--depth", "2"]).unwrap();
+        let listing = Listing {
+            by_size: true,
+            max_depth: Some(2),
+        };
+        assert_eq!((Mode::Tree { listing }, vec![7]), (tree.mode, tree.days));
+        assert_eq!(
+            Mode::Du {
+                listing: Listing::default()
+            },
+            parse(&["du", "7"]).unwrap().mode
+        );
+        assert!(parse(&["du", "tree"]).is_err());
+        assert!(parse(&["du", "--depth", "-1"]).is_err());
+
+        assert!(parse(&["26"]).is_err());
+        assert!(parse(&["0..=3"]).is_err());
+        assert!(parse(&["-p", "3"]).is_err());
+        assert!(parse(&["--part"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+        assert!(parse(&["7", "8", "--input", "mine.txt"]).is_err());
+    }
+}
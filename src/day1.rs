@@ -1,55 +1,445 @@
+use crate::parsing::{at_line, check_lines, or_parse_error};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, WrapErr};
 use itertools::Itertools;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
 
-fn get_most_carrying_elves_charge(foods: &str, number_of_elves: usize) -> usize {
-    foods
-        .lines()
-        .group_by(|l| l.is_empty())
+/// calories carried by an elf, elves being numbered from 0 in inventory order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfCharge {
+    pub elf: usize,
+    pub charge: usize,
+}
+
+/// the `n` most charged elves seen so far, the first elf winning ties
+struct TopCharges {
+    n: usize,
+    // min-heap on the charge, so that the least charged elf is the one evicted
+    heap: BinaryHeap<Reverse<(usize, Reverse<usize>)>>,
+}
+
+impl TopCharges {
+    fn new(n: usize) -> Self {
+        Self {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    fn push(&mut self, elf: usize, charge: usize) {
+        self.heap.push(Reverse((charge, Reverse(elf))));
+        if self.heap.len() > self.n {
+            self.heap.pop();
+        }
+    }
+
+    /// most charged first
+    fn into_sorted(self) -> Vec<ElfCharge> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((charge, Reverse(elf)))| ElfCharge { elf, charge })
+            .collect()
+    }
+}
+
+/// calls `elf_read` with the number of food items and the total calories of each elf
+///
+/// `foods` is read line by line, blank lines separating elves
+fn read_elves(mut foods: impl BufRead, mut elf_read: impl FnMut(usize, usize)) -> eyre::Result<()> {
+    let mut elf = 0;
+    let mut current: Option<(usize, usize)> = None;
+    let mut line = String::new();
+    for i in 0.. {
+        line.clear();
+        let read = foods
+            .read_line(&mut line)
+            .wrap_err_with(|| format!("reading {}", at_line(i)))?;
+        let food = line.trim();
+        if food.is_empty() {
+            if let Some((count, charge)) = current.take() {
+                elf_read(count, charge);
+                elf += 1;
+            }
+            if read == 0 {
+                break;
+            }
+            continue;
+        }
+        let calories: usize = food
+            .parse()
+            .wrap_err_with(|| format!("{} : '{food}'", at_line(i)))?;
+        let (count, charge) = current.unwrap_or((0, 0));
+        let charge = charge
+            .checked_add(calories)
+            .ok_or_else(|| eyre!("{} : elf {elf} carries too many calories", at_line(i)))?;
+        current = Some((count + 1, charge));
+    }
+    Ok(())
+}
+
+/// the `number_of_elves` most charged elves of an inventory, most charged first
+///
+/// `foods` is read line by line, only the top charges being kept in memory
+pub fn most_charged_elves(
+    foods: impl BufRead,
+    number_of_elves: usize,
+) -> eyre::Result<Vec<ElfCharge>> {
+    let mut top = TopCharges::new(number_of_elves);
+    let mut elf = 0;
+    read_elves(foods, |_, charge| {
+        top.push(elf, charge);
+        elf += 1;
+    })?;
+    Ok(top.into_sorted())
+}
+
+/// number of food items and total calories carried by each elf
+fn elves(foods: &str) -> eyre::Result<Vec<(usize, usize)>> {
+    let mut elves = vec![];
+    read_elves(foods.as_bytes(), |count, charge| {
+        elves.push((count, charge))
+    })?;
+    Ok(elves)
+}
+
+/// total calories carried by each elf
+fn read_charges(foods: &str) -> eyre::Result<Vec<usize>> {
+    Ok(elves(foods)?
         .into_iter()
-        .filter(|(is_sep, _)| !*is_sep)
-        .map(|(_, charges)| {
-            charges
-                .into_iter()
-                .map(|c| c.parse::<usize>().unwrap_or(0))
-                .sum::<usize>()
+        .map(|(_, charge)| charge)
+        .collect())
+}
+
+const HISTOGRAM_BUCKETS: usize = 10;
+/// length of the longest histogram bar
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// number of elves whose charge is in `start..=end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bucket {
+    pub start: usize,
+    pub end: usize,
+    pub elves: usize,
+}
+
+/// statistics about the charges of all elves of an inventory
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventoryStats {
+    /// number of food items carried by each elf
+    pub food_counts: Vec<usize>,
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub median: f64,
+    /// at most [HISTOGRAM_BUCKETS] buckets of the same width, from `min` to `max`
+    pub histogram: Vec<Bucket>,
+    /// the `n` most charged elves, most charged first
+    pub top: Vec<ElfCharge>,
+    /// elves charged as much as the n-th one, when some of them are left out of `top`
+    pub ties: Vec<usize>,
+}
+
+impl InventoryStats {
+    pub fn new(foods: &str, n: usize) -> eyre::Result<Self> {
+        let elves = elves(foods)?;
+        if elves.is_empty() {
+            bail!("no elf in inventory");
+        }
+        let charges: Vec<usize> = elves.iter().map(|(_, charge)| *charge).collect();
+        let sorted: Vec<usize> = charges.iter().copied().sorted().collect();
+        let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] as f64 + sorted[middle] as f64) / 2.0
+        } else {
+            sorted[middle] as f64
+        };
+
+        let width = (max - min) / HISTOGRAM_BUCKETS + 1;
+        let mut histogram: Vec<Bucket> = (min..=max)
+            .step_by(width)
+            .map(|start| Bucket {
+                start,
+                end: start.saturating_add(width - 1).min(max),
+                elves: 0,
+            })
+            .collect();
+        for charge in &charges {
+            histogram[(charge - min) / width].elves += 1;
+        }
+
+        let top = most_charged(&charges, n);
+        let ties = match top.last() {
+            Some(nth) => {
+                let tied: Vec<usize> = charges.iter().positions(|c| *c == nth.charge).collect();
+                let kept = top.iter().filter(|e| e.charge == nth.charge).count();
+                if tied.len() > kept {
+                    tied
+                } else {
+                    vec![]
+                }
+            }
+            None => vec![],
+        };
+
+        Ok(Self {
+            food_counts: elves.iter().map(|(count, _)| *count).collect(),
+            min,
+            max,
+            mean: charges.iter().map(|c| *c as f64).sum::<f64>() / charges.len() as f64,
+            median,
+            histogram,
+            top,
+            ties,
         })
-        .sorted()
-        .rev()
-        .take(number_of_elves)
-        .sum()
+    }
+
+    /// one line per field, numbers being written as is
+    pub fn to_json(&self) -> String {
+        let histogram = self
+            .histogram
+            .iter()
+            .map(|b| {
+                format!(
+                    "{{\"start\": {}, \"end\": {}, \"elves\": {}}}",
+                    b.start, b.end, b.elves
+                )
+            })
+            .join(", ");
+        let top = self
+            .top
+            .iter()
+            .map(|e| format!("{{\"elf\": {}, \"charge\": {}}}", e.elf, e.charge))
+            .join(", ");
+        format!(
+            "{{\n  \"elves\": {},\n  \"food_counts\": [{}],\n  \"min\": {},\n  \"max\": {},\n  \
+             \"mean\": {},\n  \"median\": {},\n  \"histogram\": [{histogram}],\n  \
+             \"top\": [{top}],\n  \"ties\": [{}]\n}}\n",
+            self.food_counts.len(),
+            self.food_counts.iter().join(", "),
+            self.min,
+            self.max,
+            self.mean,
+            self.median,
+            self.ties.iter().join(", "),
+        )
+    }
 }
 
-pub fn handle_elves_food() {
-    let foods = include_str!("../resources/day1_calories.txt");
-    let max_charge = get_most_carrying_elves_charge(foods, 1);
-    println!("Calories carried by most charged elf : {max_charge}");
-    let three_max_charges = get_most_carrying_elves_charge(foods, 3);
-    println!("Calories carried by 3 most charged elves : {three_max_charges}");
+impl Display for InventoryStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let food_counts = self.food_counts.iter().minmax().into_option();
+        let (fewest, most) = food_counts.map_or((0, 0), |(a, b)| (*a, *b));
+        writeln!(
+            f,
+            "elves      : {}, carrying {fewest} to {most} food items ({} in all)",
+            self.food_counts.len(),
+            self.food_counts.iter().sum::<usize>()
+        )?;
+        writeln!(
+            f,
+            "calories   : min {}, max {}, mean {:.1}, median {:.1}",
+            self.min, self.max, self.mean, self.median
+        )?;
+        writeln!(
+            f,
+            "top {:<6} : {}",
+            self.top.len(),
+            self.top
+                .iter()
+                .map(|e| format!("elf {} ({})", e.elf, e.charge))
+                .join(", ")
+        )?;
+        match self.ties.is_empty() {
+            true => writeln!(f, "ties       : none")?,
+            false => writeln!(
+                f,
+                "ties       : elves {} all carry {}",
+                self.ties.iter().join(", "),
+                self.top[self.top.len() - 1].charge
+            )?,
+        }
+        writeln!(f, "histogram  :")?;
+        let most_elves = self.histogram.iter().map(|b| b.elves).max().unwrap_or(0);
+        let digits = self.max.to_string().len();
+        for bucket in &self.histogram {
+            let bar = "#".repeat((bucket.elves * HISTOGRAM_WIDTH).div_ceil(most_elves.max(1)));
+            writeln!(
+                f,
+                "  {:>digits$}..={:>digits$} | {bar} {}",
+                bucket.start, bucket.end, bucket.elves
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn most_charged(charges: &[usize], number_of_elves: usize) -> Vec<ElfCharge> {
+    let mut top = TopCharges::new(number_of_elves);
+    for (elf, charge) in charges.iter().enumerate() {
+        top.push(elf, *charge);
+    }
+    top.into_sorted()
+}
+
+fn sum_charges(elves: &[ElfCharge]) -> eyre::Result<usize> {
+    elves
+        .iter()
+        .try_fold(0usize, |sum, e| sum.checked_add(e.charge))
+        .ok_or_else(|| eyre!("the most charged elves carry too many calories"))
+}
+
+fn sum_most_charged(charges: &[usize], number_of_elves: usize) -> eyre::Result<usize> {
+    sum_charges(&most_charged(charges, number_of_elves))
+}
+
+pub fn get_most_carrying_elves_charge(foods: &str, number_of_elves: usize) -> eyre::Result<usize> {
+    sum_charges(&most_charged_elves(foods.as_bytes(), number_of_elves)?)
+}
+
+pub struct Inventory {
+    charges: Vec<usize>,
+}
+
+impl Solution<'_> for Inventory {
+    fn parse(foods: &str) -> eyre::Result<Self> {
+        Ok(Self {
+            charges: read_charges(foods)?,
+        })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(sum_most_charged(&self.charges, 1)?.into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(sum_most_charged(&self.charges, 3)?.into())
+    }
+    fn check(foods: &str) -> Vec<eyre::Report> {
+        let problems = check_lines(foods, |c| Ok(c.trim().parse::<usize>()?));
+        or_parse_error(problems, || Self::parse(foods))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn most_charged_elves_are_found(elves in prop::collection::vec(prop::collection::vec(1..10000usize, 1..6), 1..20)) {
+            let foods = elves.iter().map(|foods| foods.iter().join("\n")).join("\n\n");
+            let mut charges = elves.iter().map(|foods| foods.iter().sum::<usize>()).collect_vec();
+            charges.sort_unstable_by(|a, b| b.cmp(a));
+            for n in 1..=3 {
+                prop_assert_eq!(
+                    charges.iter().take(n).sum::<usize>(),
+                    get_most_carrying_elves_charge(&foods, n).unwrap()
+                );
+            }
+        }
+    }
+
+    const FOODS: &str = indoc! {"
+    1000
+    2000
+    3000
+    
+    4000
+    
+    5000
+    6000
+    
+    7000
+    8000
+    9000
+    
+    10000"};
 
     #[test]
     fn aoc_example_1_works() {
-        let foods = indoc! {"
-        1000
-        2000
-        3000
-        
-        4000
-        
-        5000
-        6000
-        
-        7000
-        8000
-        9000
-        
-        10000"};
-
-        assert_eq!(24000, get_most_carrying_elves_charge(foods, 1));
-        assert_eq!(45000, get_most_carrying_elves_charge(foods, 3));
+        assert_eq!(24000, get_most_carrying_elves_charge(FOODS, 1).unwrap());
+        assert_eq!(45000, get_most_carrying_elves_charge(FOODS, 3).unwrap());
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        assert!(get_most_carrying_elves_charge("1000\n2OOO", 1).is_err());
+    }
+
+    #[test]
+    fn most_charged_elves_are_streamed() {
+        assert_eq!(
+            vec![
+                ElfCharge {
+                    elf: 3,
+                    charge: 24000
+                },
+                ElfCharge {
+                    elf: 2,
+                    charge: 11000
+                }
+            ],
+            most_charged_elves(FOODS.as_bytes(), 2).unwrap()
+        );
+        assert_eq!(5, most_charged_elves(FOODS.as_bytes(), 10).unwrap().len());
+
+        let ties = most_charged_elves("5\n\n3\n2\n\n\n\n4\n1\n".as_bytes(), 1).unwrap();
+        assert_eq!(vec![ElfCharge { elf: 0, charge: 5 }], ties);
+
+        let error = most_charged_elves("1000\n\n2000\n3OOO\n".as_bytes(), 1).unwrap_err();
+        assert_eq!("line 4 : '3OOO'", error.to_string());
+        assert!(most_charged_elves(&b"1\n\xff\n"[..], 1).is_err());
+    }
+
+    #[test]
+    fn inventory_stats_work() {
+        let stats = InventoryStats::new(FOODS, 3).unwrap();
+        assert_eq!(vec![3, 1, 2, 3, 1], stats.food_counts);
+        assert_eq!((4000, 24000), (stats.min, stats.max));
+        assert_eq!((11000.0, 10000.0), (stats.mean, stats.median));
+        assert_eq!(
+            Bucket {
+                start: 4000,
+                end: 6000,
+                elves: 2
+            },
+            stats.histogram[0]
+        );
+        assert_eq!(5, stats.histogram.iter().map(|b| b.elves).sum::<usize>());
+        assert_eq!(45000, stats.top.iter().map(|e| e.charge).sum::<usize>());
+        assert!(stats.ties.is_empty());
+        assert!(stats
+            .to_string()
+            .contains("top 3      : elf 3 (24000), elf 2 (11000), elf 4 (10000)"));
+        assert!(stats
+            .to_json()
+            .contains("\"food_counts\": [3, 1, 2, 3, 1],"));
+
+        let stats = InventoryStats::new("5\n\n3\n\n5\n\n5\n", 2).unwrap();
+        assert_eq!(vec![0, 2, 3], stats.ties);
+        assert_eq!(3, stats.histogram.len());
+        assert!(InventoryStats::new("\n", 2).is_err());
+    }
+
+    #[test]
+    fn overflows_are_errors() {
+        let foods = "18446744073709551615\n2\n";
+        assert!(Inventory::parse(foods).is_err());
+        assert!(InventoryStats::new(foods, 3).is_err());
+        assert!(most_charged_elves(foods.as_bytes(), 3).is_err());
+        assert_eq!(1, Inventory::check(foods).len());
+
+        let foods = "9223372036854775808\n  \n9223372036854775808\n";
+        let inventory = Inventory::parse(foods).unwrap();
+        assert_eq!(Answer::Int(9223372036854775808), inventory.part1().unwrap());
+        assert!(inventory.part2().is_err());
+        let stats = InventoryStats::new(foods, 2).unwrap();
+        assert_eq!(vec![1, 1], stats.food_counts);
+        assert_eq!(9223372036854775808.0, stats.median);
     }
 }
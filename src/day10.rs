@@ -1,4 +1,6 @@
 use crate::day10::Command::Noop;
+use crate::parsing::{check_lines, parse_lines};
+use crate::solution::{Answer, Solution};
 use eyre::{eyre, Context, ContextCompat};
 use itertools::Itertools;
 use std::str::FromStr;
@@ -28,13 +30,14 @@ impl FromStr for Command {
             "addx" => items
                 .next()
                 .context("trying to read value")
-                .and_then(|v| v.parse::<isize>().context("parsing {v}"))
+                .and_then(|v| v.parse::<isize>().with_context(|| format!("parsing {v}")))
                 .map(Command::Add),
             _ => Err(eyre!("cannot parse '{s}'")),
         }
     }
 }
-struct Computer {
+#[derive(Clone)]
+pub struct Computer {
     x: isize,
     time: usize,
     program: Vec<Command>,
@@ -42,17 +45,13 @@ struct Computer {
 }
 
 impl Computer {
-    fn new(source: &str) -> Self {
-        let program = source
-            .lines()
-            .filter_map(|inst| inst.parse().ok())
-            .collect();
-        Self {
+    pub fn new(source: &str) -> eyre::Result<Self> {
+        Ok(Self {
             x: 1,
             time: 0,
-            program,
+            program: parse_lines(source, str::parse)?,
             sp: 0,
-        }
+        })
     }
     fn execute_next(&mut self) -> usize {
         let cmd = self.program[self.sp];
@@ -67,21 +66,19 @@ impl Computer {
 
         cmd.len()
     }
-    fn power_at_time(&mut self, time: usize) -> isize {
+    pub fn power_at_time(&mut self, time: usize) -> isize {
         let mut margin = time - self.time;
-        while margin > self.program[self.sp].len() {
+        // once the program is over, x keeps its last value
+        while self.sp < self.program.len() && margin > self.program[self.sp].len() {
             margin -= self.execute_next();
         }
-        // println!("last update time is {}, last value is {}", self.time, self.x);
         self.x * time as isize
     }
 
-    fn display_screen(mut self) -> String {
+    pub fn display_screen(mut self) -> String {
         (0..6)
-            .into_iter()
             .map(|r| {
                 (0..40)
-                    .into_iter()
                     .map(|c| {
                         let pixel_time = r * 40 + c + 1;
                         self.power_at_time(pixel_time);
@@ -97,22 +94,31 @@ impl Computer {
     }
 }
 
-pub fn decode_signal() {
-    let source = include_str!("../resources/day10_source.txt");
-    let mut computer = Computer::new(source);
-    let strength_sum: isize = [20, 60, 100, 140, 180, 220]
-        .into_iter()
-        .map(|v| computer.power_at_time(v))
-        .sum();
-    println!("strength_sum : {strength_sum}");
-    let computer = Computer::new(source);
-    println!("{}", computer.display_screen());
+impl Solution<'_> for Computer {
+    fn parse(source: &str) -> eyre::Result<Self> {
+        Computer::new(source)
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        let mut computer = self.clone();
+        let strength_sum: isize = [20, 60, 100, 140, 180, 220]
+            .into_iter()
+            .map(|v| computer.power_at_time(v))
+            .sum();
+        Ok(strength_sum.into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(Answer::Screen(self.clone().display_screen()))
+    }
+    fn check(source: &str) -> Vec<eyre::Report> {
+        check_lines(source, str::parse::<Command>)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn aoc_example_works() {
@@ -264,7 +270,7 @@ mod tests {
             noop
             noop
         "};
-        let mut computer = Computer::new(source);
+        let mut computer = Computer::new(source).unwrap();
         assert_eq!(420, computer.power_at_time(20));
         assert_eq!(1140, computer.power_at_time(60));
         assert_eq!(1800, computer.power_at_time(100));
@@ -272,7 +278,7 @@ mod tests {
         assert_eq!(2880, computer.power_at_time(180));
         assert_eq!(3960, computer.power_at_time(220));
 
-        let mut computer = Computer::new(source);
+        let mut computer = Computer::new(source).unwrap();
         assert_eq!(
             13140isize,
             [20, 60, 100, 140, 180, 220]
@@ -281,7 +287,55 @@ mod tests {
                 .sum()
         );
 
-        let computer = Computer::new(source);
+        let computer = Computer::new(source).unwrap();
         println!("{}", computer.display_screen());
     }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = Computer::new("noop\naddx 1\naddx\n").err().unwrap();
+        assert_eq!("line 3 : 'addx'", error.to_string());
+    }
+
+    fn program() -> impl Strategy<Value = Vec<Option<isize>>> {
+        prop::collection::vec(prop::option::of(-20..20isize), 0..150)
+    }
+
+    proptest! {
+        #[test]
+        fn program_can_be_run(program in program()) {
+            let source = program
+                .iter()
+                .map(|cmd| match cmd {
+                    None => "noop\n".to_string(),
+                    Some(v) => format!("addx {v}\n"),
+                })
+                .collect::<String>();
+            // x during each cycle, keeping its last value once the program is over
+            let mut xs = vec![];
+            let mut x = 1;
+            for cmd in &program {
+                xs.push(x);
+                if let Some(v) = cmd {
+                    xs.push(x);
+                    x += v;
+                }
+            }
+            let x_during = |cycle: usize| xs.get(cycle - 1).copied().unwrap_or(x);
+            let computer = Computer::new(&source).unwrap();
+            let strength: isize = [20, 60, 100, 140, 180, 220]
+                .into_iter()
+                .map(|t| t as isize * x_during(t))
+                .sum();
+            prop_assert_eq!(Answer::from(strength), computer.part1().unwrap());
+            let screen = (0..6)
+                .map(|r| {
+                    (0..40)
+                        .map(|c| if (x_during(r * 40 + c + 1) - c as isize).abs() <= 1 { '#' } else { ' ' })
+                        .collect::<String>()
+                })
+                .join("\n");
+            prop_assert_eq!(screen, computer.display_screen());
+        }
+    }
 }
@@ -1,9 +1,10 @@
-use eyre::{eyre, Context, ContextCompat};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, Context, ContextCompat};
 use itertools::Itertools;
 use std::collections::VecDeque;
 use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Operation {
     Plus(usize),
     Mult(usize),
@@ -27,22 +28,24 @@ impl FromStr for Operation {
         let remain = s
             .strip_prefix(OPERATION_PREFIX)
             .with_context(|| format!("'{s}' does not start with '{OPERATION_PREFIX}'"))?;
-        let char = remain.chars().next().context("reading operator")?;
+        let mut chars = remain.chars();
+        let char = chars.next().context("reading operator")?;
+        let operand = chars.as_str();
         match char {
-            '+' => remain[1..]
+            '+' => operand
                 .trim()
                 .parse()
                 .map(Self::Plus)
-                .with_context(|| format!("reading operand fom '{}'", &remain[1..])),
+                .with_context(|| format!("reading operand fom '{operand}'")),
             '*' => {
-                if &remain[1..] == " old" {
+                if operand == " old" {
                     Ok(Self::Square)
                 } else {
-                    remain[1..]
+                    operand
                         .trim()
                         .parse()
                         .map(Self::Mult)
-                        .with_context(|| format!("reading operand fom '{}'", &remain[1..]))
+                        .with_context(|| format!("reading operand fom '{operand}'"))
                 }
             }
             _ => Err(eyre!("wrong operator !")),
@@ -50,7 +53,7 @@ impl FromStr for Operation {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Monkey {
     items: VecDeque<usize>,
     oper: Operation,
@@ -108,8 +111,8 @@ impl FromStr for Monkey {
     }
 }
 
-#[derive(Debug)]
-struct Game {
+#[derive(Debug, Clone)]
+pub struct Game {
     monkeys: Vec<Monkey>,
     divisors_ppcm: usize,
 }
@@ -127,9 +130,28 @@ impl FromStr for Game {
         } else {
             "\n\n"
         };
-        let monkeys: Result<Vec<Monkey>, _> = s.split(separator).map(|m| m.parse()).collect();
+        let monkeys: Result<Vec<Monkey>, _> = s
+            .split(separator)
+            .filter(|m| !m.trim().is_empty())
+            .enumerate()
+            .map(|(i, m)| m.parse().wrap_err_with(|| format!("monkey {i}")))
+            .collect();
         let monkeys = monkeys?;
-        let divisors_ppcm: usize = monkeys.iter().map(|m| m.divisor).product();
+        for (i, monkey) in monkeys.iter().enumerate() {
+            if monkey.divisor == 0 {
+                bail!("monkey {i} tests divisibility by 0");
+            }
+            if let Some(dest) = [monkey.if_true, monkey.if_false]
+                .into_iter()
+                .find(|d| *d >= monkeys.len())
+            {
+                bail!("monkey {i} throws to unknown monkey {dest}");
+            }
+        }
+        let divisors_ppcm: usize = monkeys
+            .iter()
+            .try_fold(1usize, |ppcm, m| ppcm.checked_mul(m.divisor))
+            .context("the product of all divisors overflows")?;
         Ok(Self {
             monkeys,
             divisors_ppcm,
@@ -158,7 +180,7 @@ impl Game {
             self.monkeys[throw.dest].items.push_back(throw.item);
         }
     }
-    fn play_round(&mut self, decrease_factor: usize) {
+    pub fn play_round(&mut self, decrease_factor: usize) {
         let len = self.monkeys.len();
         for i in 0..len {
             self.single_play(i, decrease_factor);
@@ -166,42 +188,101 @@ impl Game {
     }
 }
 
-pub fn chase_monkeys() {
-    let input = include_str!("../resources/day11_monkeys.txt");
-
-    let mut game: Game = input.parse().expect("reading input");
-    for _ in 0..20 {
-        game.play_round(3);
+impl Game {
+    pub fn monkey_business_level(mut self, rounds: usize, relief: usize) -> usize {
+        for _ in 0..rounds {
+            self.play_round(relief);
+        }
+        self.monkeys
+            .iter()
+            .map(|m| m.inspects)
+            .sorted()
+            .rev()
+            .take(2)
+            .product()
     }
-    let level: usize = game
-        .monkeys
-        .iter()
-        .map(|m| m.inspects)
-        .sorted()
-        .rev()
-        .take(2)
-        .product();
-    println!("level of monkeys after 20 round is {level}");
+}
 
-    let mut game: Game = input.parse().expect("reading input");
-    for _ in 0..10000 {
-        game.play_round(1);
+impl Solution<'_> for Game {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        input.parse()
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(self.clone().monkey_business_level(20, 3).into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(self.clone().monkey_business_level(10000, 1).into())
     }
-    let level: usize = game
-        .monkeys
-        .iter()
-        .map(|m| m.inspects)
-        .sorted()
-        .rev()
-        .take(2)
-        .product();
-    println!("level of monkeys after 10000 round is {level}");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
+
+    fn operation() -> impl Strategy<Value = String> {
+        prop_oneof![
+            (0..20usize).prop_map(|v| format!("+ {v}")),
+            (0..20usize).prop_map(|v| format!("* {v}")),
+            Just("* old".to_string()),
+        ]
+    }
+
+    /// items of each monkey, and the notes describing them
+    fn monkey_notes() -> impl Strategy<Value = (Vec<Vec<usize>>, String)> {
+        (2..6usize)
+            .prop_flat_map(|n| {
+                prop::collection::vec(
+                    (
+                        prop::collection::vec(0..100usize, 1..5),
+                        operation(),
+                        1..20usize,
+                        0..n,
+                        0..n,
+                    ),
+                    n,
+                )
+            })
+            .prop_map(|monkeys| {
+                let notes = monkeys
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (items, oper, divisor, if_true, if_false))| {
+                        format!(
+                            "Monkey {i}:\n  \
+                             Starting items: {}\n  \
+                             Operation: new = old {oper}\n  \
+                             Test: divisible by {divisor}\n    \
+                             If true: throw to monkey {if_true}\n    \
+                             If false: throw to monkey {if_false}",
+                            items.iter().join(", ")
+                        )
+                    })
+                    .join("\n\n");
+                (monkeys.into_iter().map(|m| m.0).collect(), notes)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn monkey_notes_can_be_read((items, notes) in monkey_notes()) {
+            let game: Game = notes.parse().unwrap();
+            prop_assert_eq!(
+                items,
+                game.monkeys.iter().map(|m| Vec::from(m.items.clone())).collect_vec()
+            );
+            // each item is inspected at most once per monkey and round
+            let item_count = game.monkeys.iter().map(|m| m.items.len()).sum::<usize>();
+            let level = game.monkey_business_level(20, 3);
+            prop_assert!(level <= (20 * item_count).pow(2));
+        }
+
+        #[test]
+        fn monkey_parsing_does_not_panic(s in "Monkey 0:\n  Starting items: [0-9, ]{0,6}\n  Operation: new = old .{0,5}\n  Test: divisible by [0-9]{1,2}\n    If true: throw to monkey [0-2]\n    If false: throw to monkey [0-2]") {
+            let _ = s.parse::<Game>();
+        }
+    }
 
     #[test]
     fn aoc_example_works() {
@@ -268,4 +349,18 @@ mod tests {
                 .product()
         );
     }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let wrong_target = indoc! {"
+            Monkey 0:
+              Starting items: 79, 98
+              Operation: new = old * 19
+              Test: divisible by 23
+                If true: throw to monkey 0
+                If false: throw to monkey 7
+        "};
+        let error = wrong_target.parse::<Game>().unwrap_err();
+        assert_eq!("monkey 0 throws to unknown monkey 7", error.to_string());
+    }
 }
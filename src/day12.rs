@@ -1,10 +1,13 @@
 use std::cmp::max;
 use std::collections::HashSet;
 
+use crate::parsing::{at, at_line, check_chars, or_parse_error};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, ContextCompat};
 use std::str::FromStr;
 
 #[derive(Debug)]
-struct Map {
+pub struct Map {
     elevations: Vec<Vec<u8>>,
     start: (usize, usize),
     end: (usize, usize),
@@ -14,34 +17,37 @@ impl FromStr for Map {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut start = (0, 0);
-        let mut end = (0, 0);
-
-        let elevations = s
-            .lines()
-            .enumerate()
-            .map(|(j, l)| {
-                l.chars()
-                    .enumerate()
-                    .filter_map(|(i, c)| match c {
-                        'a'..='z' => Some(c as u8 - b'a' + 1),
-                        'S' => {
-                            start = (i, j);
-                            Some(1)
-                        }
-                        'E' => {
-                            end = (i, j);
-                            Some(26)
-                        }
-                        _ => None,
-                    })
-                    .collect()
-            })
-            .collect();
+        let mut start = None;
+        let mut end = None;
+
+        let mut elevations: Vec<Vec<u8>> = vec![];
+        for (j, l) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let mut row = vec![];
+            for (i, c) in l.trim_end().chars().enumerate() {
+                let pos = (i, elevations.len());
+                row.push(match c {
+                    'a'..='z' => c as u8 - b'a' + 1,
+                    'S' if start.is_none() => {
+                        start = Some(pos);
+                        1
+                    }
+                    'E' if end.is_none() => {
+                        end = Some(pos);
+                        26
+                    }
+                    'S' | 'E' => bail!("second '{c}' at {}", at(j, i)),
+                    _ => bail!("invalid elevation '{c}' at {}", at(j, i)),
+                });
+            }
+            if elevations.first().is_some_and(|r| r.len() != row.len()) {
+                bail!("{} is not as wide as the first row", at_line(j));
+            }
+            elevations.push(row);
+        }
         Ok(Self {
             elevations,
-            start,
-            end,
+            start: start.context("no start 'S' on map")?,
+            end: end.context("no end 'E' on map")?,
         })
     }
 }
@@ -137,7 +143,7 @@ impl<'m> Path<'m> {
     }
 }
 
-fn find_shortest_slope(map: &Map) -> usize {
+pub fn find_shortest_slope(map: &Map) -> eyre::Result<usize> {
     let starts: Vec<_> = map
         .elevations
         .iter()
@@ -156,18 +162,27 @@ fn find_shortest_slope(map: &Map) -> usize {
             path.find_shortest_path_len()
         })
         .min()
-        .unwrap()
+        .context("the end cannot be reached from any lowest square")
 }
-pub fn climb_hills() {
-    let elevations = include_str!("../resources/day12_elevations.txt");
-    let map: Map = elevations.parse().expect("could not parse map");
-    {
-        let path = Path::new(&map, map.start);
-        let shortest = path.find_shortest_path_len().unwrap();
-        println!("shortest path {shortest}");
-
-        let shortest_slope = find_shortest_slope(&map);
-        println!("shortest slope {shortest_slope}");
+impl Solution<'_> for Map {
+    fn parse(elevations: &str) -> eyre::Result<Self> {
+        elevations.parse()
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        let path = Path::new(self, self.start);
+        let len = path
+            .find_shortest_path_len()
+            .context("the end cannot be reached from the start")?;
+        Ok(len.into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(find_shortest_slope(self)?.into())
+    }
+    fn check(elevations: &str) -> Vec<eyre::Report> {
+        or_parse_error(
+            check_chars(elevations, "abcdefghijklmnopqrstuvwxyzSE"),
+            || elevations.parse::<Self>(),
+        )
     }
 }
 
@@ -175,6 +190,9 @@ pub fn climb_hills() {
 mod tests {
     use super::*;
     use indoc::indoc;
+    use itertools::Itertools;
+    use proptest::prelude::*;
+    use std::collections::VecDeque;
 
     #[test]
     fn aoc_examples_work() {
@@ -189,6 +207,104 @@ mod tests {
         let map: Map = elevations.parse().expect("could not parse map");
         let path = Path::new(&map, map.start);
         assert_eq!(31, path.find_shortest_path_len().unwrap());
-        assert_eq!(29, find_shortest_slope(&map));
+        assert_eq!(29, find_shortest_slope(&map).unwrap());
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = "Sab\nac?E\n".parse::<Map>().unwrap_err();
+        assert_eq!(
+            "invalid elevation '?' at line 2, column 3",
+            error.to_string()
+        );
+        assert!("Sab\nabc\n".parse::<Map>().is_err());
+    }
+
+    /// a heightmap rising from west to east, with S and E at distinct positions
+    fn heightmap() -> impl Strategy<Value = Vec<Vec<char>>> {
+        (1..8usize, 2..10usize)
+            .prop_flat_map(|(rows, cols)| {
+                (
+                    prop::collection::vec(prop::collection::vec(-3..=1isize, cols), rows),
+                    Just((0..rows * cols).collect::<Vec<_>>()).prop_shuffle(),
+                )
+            })
+            .prop_map(|(noises, positions)| {
+                let cols = noises[0].len();
+                let mut squares = noises
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .enumerate()
+                            .map(|(c, noise)| {
+                                let elevation = (c * 25 / (cols - 1)) as isize + noise;
+                                (b'a' + elevation.clamp(0, 25) as u8) as char
+                            })
+                            .collect_vec()
+                    })
+                    .collect_vec();
+                squares[positions[0] / cols][positions[0] % cols] = 'S';
+                squares[positions[1] / cols][positions[1] % cols] = 'E';
+                squares
+            })
+    }
+
+    /// breadth first search of the steps from the nearest of `starts` to `end`
+    fn steps(
+        elevations: &[Vec<u8>],
+        starts: &[(usize, usize)],
+        end: (usize, usize),
+    ) -> Option<usize> {
+        let mut steps = vec![vec![None; elevations[0].len()]; elevations.len()];
+        let mut queue: VecDeque<(usize, usize)> = starts.iter().copied().collect();
+        for (r, c) in starts {
+            steps[*r][*c] = Some(0);
+        }
+        while let Some((r, c)) = queue.pop_front() {
+            let next = [
+                (r.wrapping_sub(1), c),
+                (r + 1, c),
+                (r, c.wrapping_sub(1)),
+                (r, c + 1),
+            ];
+            for (nr, nc) in next {
+                if nr < elevations.len()
+                    && nc < elevations[0].len()
+                    && steps[nr][nc].is_none()
+                    && elevations[nr][nc] <= elevations[r][c] + 1
+                {
+                    steps[nr][nc] = steps[r][c].map(|s| s + 1);
+                    queue.push_back((nr, nc));
+                }
+            }
+        }
+        steps[end.0][end.1]
+    }
+
+    proptest! {
+        #[test]
+        fn heightmap_can_be_read(squares in heightmap()) {
+            let map: Map = squares.iter().map(|row| row.iter().collect::<String>()).join("\n").parse().unwrap();
+            let find = |c: char| (0..squares.len())
+                .cartesian_product(0..squares[0].len())
+                .filter(|(r, col)| squares[*r][*col] == c)
+                .collect_vec();
+            let elevations = squares
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|c| match c {
+                            'S' => 0,
+                            'E' => 25,
+                            _ => *c as u8 - b'a',
+                        })
+                        .collect_vec()
+                })
+                .collect_vec();
+            let end = find('E')[0];
+            let expected = |starts: &[(usize, usize)]| steps(&elevations, starts, end).map(Answer::from);
+            prop_assert_eq!(expected(&find('S')), map.part1().ok());
+            prop_assert_eq!(expected(&[find('S'), find('a')].concat()), map.part2().ok());
+        }
     }
 }
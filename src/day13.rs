@@ -1,9 +1,16 @@
-use itertools::{merge, Itertools};
+use crate::parsing::{check_lines, or_parse_error, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, WrapErr};
+use itertools::Itertools;
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+/// packets are read recursively, deeper ones could overflow the stack
+const MAX_DEPTH: usize = 256;
 
 #[derive(PartialEq, Eq, Clone, Debug)]
-enum PacketData {
+pub enum PacketData {
     List(Vec<PacketData>),
     Int(usize),
 }
@@ -13,8 +20,11 @@ impl Display for PacketData {
         match self {
             PacketData::List(datas) => {
                 f.write_char('[')?;
-                for d in datas {
-                    f.write_str(&format!("{d},"))?;
+                for (i, d) in datas.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{d}")?;
                 }
                 f.write_char(']')
             }
@@ -24,7 +34,7 @@ impl Display for PacketData {
 }
 
 impl PacketData {
-    fn cmp_list(first: &Vec<PacketData>, other: &Vec<PacketData>) -> Ordering {
+    fn cmp_list(first: &[PacketData], other: &[PacketData]) -> Ordering {
         let length_independant_ord = first
             .iter()
             .zip(other)
@@ -37,50 +47,65 @@ impl PacketData {
         }
     }
 
-    fn new(input: &str) -> (Self, usize) {
-        match input.as_bytes()[0] {
-            b'[' => {
+    /// reads the packet starting at `pos`, moving `pos` after it
+    fn read(input: &[u8], pos: &mut usize, depth: usize) -> eyre::Result<Self> {
+        match input.get(*pos) {
+            Some(b'[') => {
+                if depth == MAX_DEPTH {
+                    bail!("more than {MAX_DEPTH} nested lists at column {}", *pos + 1);
+                }
+                *pos += 1;
                 let mut datas: Vec<PacketData> = vec![];
-                let mut start_idx = 1usize;
-
-                'main: while let Some(end_idx) = input[start_idx..].find(&['[', ',', ']'][..]) {
-                    if end_idx > 0 {
-                        datas.push(Self::new(&input[start_idx..(start_idx + end_idx)]).0);
-                    }
-                    match input.as_bytes()[start_idx + end_idx] {
-                        b'[' => {
-                            let (data, len) = Self::new(&input[(start_idx + end_idx)..]);
-
-                            datas.push(data);
-                            start_idx += len - 1;
-                        }
-                        b']' => {
-                            start_idx += end_idx + 1;
-                            break 'main;
+                if input.get(*pos) == Some(&b']') {
+                    *pos += 1;
+                    return Ok(Self::List(datas));
+                }
+                loop {
+                    datas.push(Self::read(input, pos, depth + 1)?);
+                    match input.get(*pos) {
+                        Some(b',') => *pos += 1,
+                        Some(b']') => {
+                            *pos += 1;
+                            return Ok(Self::List(datas));
                         }
-                        b',' => {}
-                        _ => {}
+                        c => return Err(unexpected(c, *pos)),
                     }
-                    let end = start_idx + end_idx + 1;
-                    start_idx = end;
                 }
-                (Self::List(datas), start_idx)
-            }
-            b']' => {
-                panic!("unexpected char ']'");
             }
-            _ => {
-                let len = input
-                    .as_bytes()
+            Some(c) if c.is_ascii_digit() => {
+                let len = input[*pos..]
                     .iter()
-                    .enumerate()
-                    .find(|(_, c)| !c.is_ascii_digit())
-                    .map(|(i, _)| i)
-                    .unwrap_or_else(|| input.len());
-                let val: usize = input[0..len].parse().unwrap();
-                (Self::Int(val), len)
+                    .take_while(|c| c.is_ascii_digit())
+                    .count();
+                let val: usize = std::str::from_utf8(&input[*pos..*pos + len])?
+                    .parse()
+                    .wrap_err_with(|| format!("reading integer at column {}", *pos + 1))?;
+                *pos += len;
+                Ok(Self::Int(val))
             }
+            c => Err(unexpected(c, *pos)),
+        }
+    }
+}
+
+fn unexpected(c: Option<&u8>, pos: usize) -> eyre::Report {
+    match c {
+        Some(c) => eyre!("unexpected '{}' at column {}", c.escape_ascii(), pos + 1),
+        None => eyre!("unexpected end of packet"),
+    }
+}
+
+impl FromStr for PacketData {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        let input = s.trim_end().as_bytes();
+        let mut pos = 0;
+        let packet = Self::read(input, &mut pos, 0)?;
+        if pos < input.len() {
+            return Err(unexpected(input.get(pos), pos));
         }
+        Ok(packet)
     }
 }
 
@@ -95,11 +120,11 @@ impl Ord for PacketData {
         match self {
             PacketData::List(datas) => match other {
                 PacketData::List(other_datas) => PacketData::cmp_list(datas, other_datas),
-                PacketData::Int(ov) => PacketData::cmp_list(datas, &vec![PacketData::Int(*ov)]),
+                PacketData::Int(ov) => PacketData::cmp_list(datas, &[PacketData::Int(*ov)]),
             },
             PacketData::Int(v) => match other {
                 PacketData::List(other_datas) => {
-                    PacketData::cmp_list(&vec![PacketData::Int(*v)], other_datas)
+                    PacketData::cmp_list(&[PacketData::Int(*v)], other_datas)
                 }
                 PacketData::Int(ov) => v.cmp(ov),
             },
@@ -107,59 +132,113 @@ impl Ord for PacketData {
     }
 }
 
-fn parse_pairs(input: &str) -> Vec<Vec<PacketData>> {
-    let sep = if input.contains("\r\n") {
-        "\r\n\r\n"
-    } else {
-        "\n\n"
-    };
-    input
-        .split(sep)
-        .map(|input| input.lines().map(|l| PacketData::new(l).0).collect())
-        .collect()
+pub fn parse_packets(input: &str) -> eyre::Result<Vec<PacketData>> {
+    parse_lines(input, str::parse)
 }
 
-fn sum_ordered_pairs(input: &str) -> usize {
-    let pairs = parse_pairs(input);
+pub fn parse_pairs(input: &str) -> eyre::Result<Vec<(PacketData, PacketData)>> {
+    let packets = parse_packets(input)?;
+    if !packets.len().is_multiple_of(2) {
+        bail!("odd number of packets ({})", packets.len());
+    }
+    Ok(packets.into_iter().tuples().collect())
+}
 
+fn sum_ordered(pairs: &[(PacketData, PacketData)]) -> usize {
     pairs
-        .into_iter()
+        .iter()
         .enumerate()
-        .filter(|(_, p)| p[0] < p[1])
+        .filter(|(_, (left, right))| left < right)
         .map(|(i, _)| i + 1)
         .sum()
 }
 
-fn decode(input: &str) -> usize {
-    let packets = merge(
-        input.lines().filter(|l| !l.is_empty()),
-        "[[2]]\n[[6]]".lines(),
-    )
-    .map(|l| PacketData::new(l).0)
-    .sorted();
+pub fn sum_ordered_pairs(input: &str) -> eyre::Result<usize> {
+    Ok(sum_ordered(&parse_pairs(input)?))
+}
 
-    let div1 = PacketData::new("[[2]]").0;
-    let div2 = PacketData::new("[[6]]").0;
+fn divider(value: usize) -> PacketData {
+    PacketData::List(vec![PacketData::List(vec![PacketData::Int(value)])])
+}
 
+fn decode_key(packets: &[PacketData]) -> usize {
+    let dividers = [divider(2), divider(6)];
     packets
+        .iter()
+        .chain(&dividers)
+        .sorted()
         .enumerate()
-        .filter(|(_, p)| p == &div1 || p == &div2)
+        .filter(|(_, p)| dividers.contains(p))
         .map(|(i, _)| i + 1)
         .product()
 }
-pub fn distress_signal() {
-    let input = include_str!("../resources/day13_pairs.txt");
-    let sum = sum_ordered_pairs(input);
-    println!("valid pairs sum {sum}");
 
-    let key = decode(input);
-    println!("decoder key {key}");
+pub fn decode(input: &str) -> eyre::Result<usize> {
+    Ok(decode_key(&parse_packets(input)?))
+}
+
+pub struct DistressSignal {
+    pairs: Vec<(PacketData, PacketData)>,
+}
+
+impl Solution<'_> for DistressSignal {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        Ok(Self {
+            pairs: parse_pairs(input)?,
+        })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(sum_ordered(&self.pairs).into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        let packets: Vec<PacketData> = self
+            .pairs
+            .iter()
+            .flat_map(|(left, right)| [left.clone(), right.clone()])
+            .collect();
+        Ok(decode_key(&packets).into())
+    }
+    fn check(input: &str) -> Vec<eyre::Report> {
+        or_parse_error(check_lines(input, str::parse::<PacketData>), || {
+            parse_pairs(input)
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
+
+    fn packet() -> impl Strategy<Value = PacketData> {
+        (0..20usize)
+            .prop_map(PacketData::Int)
+            .prop_recursive(4, 32, 5, |inner| {
+                prop::collection::vec(inner, 0..5).prop_map(PacketData::List)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn packet_can_be_read_back(p in packet()) {
+            prop_assert_eq!(&p, &p.to_string().parse::<PacketData>().unwrap());
+        }
+
+        #[test]
+        fn packet_order_is_total(a in packet(), b in packet(), c in packet()) {
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+            prop_assert_eq!(Ordering::Equal, a.cmp(&a));
+            if a <= b && b <= c {
+                prop_assert!(a <= c);
+            }
+        }
+
+        #[test]
+        fn packet_parsing_does_not_panic(s in "[\\[\\],0-9 a]{0,40}") {
+            let _ = s.parse::<PacketData>();
+        }
+    }
 
     #[test]
     fn aoc_example_works() {
@@ -188,7 +267,21 @@ mod tests {
             [1,[2,[3,[4,[5,6,7]]]],8,9]
             [1,[2,[3,[4,[5,6,0]]]],8,9]
         "};
-        assert_eq!(13, sum_ordered_pairs(input));
-        assert_eq!(140, decode(input));
+        assert_eq!(13, sum_ordered_pairs(input).unwrap());
+        assert_eq!(140, decode(input).unwrap());
+        assert_eq!(
+            "[1,[],[2,3]]",
+            "[1,[],[2,3]]".parse::<PacketData>().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = sum_ordered_pairs("[1,2]\n[1,[2,3]\n").unwrap_err();
+        assert_eq!("line 2 : '[1,[2,3]'", error.to_string());
+        assert!("[1,2]]".parse::<PacketData>().is_err());
+        assert!("[1,a]".parse::<PacketData>().is_err());
+        assert!(sum_ordered_pairs("[1]\n[2]\n\n[3]\n").is_err());
+        assert!("[".repeat(100_000).parse::<PacketData>().is_err());
     }
 }
@@ -1,5 +1,8 @@
 use crate::day14::Material::{Air, Rock, Sand};
-use eyre::{eyre, Context};
+use crate::parsing::{check_lines, or_parse_error, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, Context};
+use itertools::Itertools;
 use std::cmp::{max, min};
 use std::fmt::{Display, Formatter, Write};
 use std::str::FromStr;
@@ -20,92 +23,86 @@ impl Display for Material {
     }
 }
 
+#[derive(Clone)]
 struct Scene {
     materials: Vec<Vec<Material>>,
 
     max_x: usize,
     max_y: usize,
+    /// where the sand comes from, as 500,0 once the columns are shifted
+    source: (usize, usize),
 
     last_fall: Vec<(usize, usize)>,
 }
 
+/// "498,4 -> 498,6 -> 496,6"
+fn read_rock(rock: &str) -> eyre::Result<Vec<(usize, usize)>> {
+    let coords: Vec<(usize, usize)> = rock
+        .split(" -> ")
+        .map(|coord_str| {
+            let mut parts = coord_str.trim().split(',');
+            let x: usize = parts.next().map_or_else(
+                || Err(eyre!("no x in {coord_str}")),
+                |c| c.parse().context("reading x"),
+            )?;
+            let y: usize = parts.next().map_or_else(
+                || Err(eyre!("no y in {coord_str}")),
+                |c| c.parse().context("reading y"),
+            )?;
+            Ok((x, y))
+        })
+        .collect::<eyre::Result<_>>()?;
+    if let Some(((prev_x, prev_y), (x, y))) = coords
+        .iter()
+        .tuple_windows()
+        .find(|((prev_x, prev_y), (x, y))| x != prev_x && y != prev_y)
+    {
+        bail!("no strait line from ({prev_x},{prev_y}) to ({x},{y})");
+    }
+    Ok(coords)
+}
+
 impl FromStr for Scene {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rocks = s.lines();
-
-        // FIXME : error_handling
+        let rocks: Vec<Vec<(usize, usize)>> = parse_lines(s, read_rock)?;
 
+        let max_y: usize = rocks.iter().flatten().map(|(_, y)| *y).max().unwrap_or(0);
+        // the sand spreads by a column at most for each row down to the floor :
+        // shifting the columns by the floor depth keeps them all positive
+        let shift = max_y + 2;
         // note : max_x is max for the build only
-        let max_x: usize = rocks
-            .clone()
-            .flat_map(|l| l.split(" -> "))
-            .filter_map(|coord| {
-                coord[0..coord.find(',').expect("no ',' in coord")]
-                    .parse()
-                    .ok()
-            })
-            .max()
-            .unwrap();
-        let max_y: usize = rocks
-            .clone()
-            .flat_map(|l| l.split(" -> "))
-            .filter_map(|coord| {
-                let coord = &coord[(1 + coord.find(',').expect("no ',' in coord"))..];
-                coord.parse::<usize>().ok()
-            })
-            .max()
-            .unwrap();
+        let max_x: usize = shift + rocks.iter().flatten().map(|(x, _)| *x).max().unwrap_or(0);
 
         let mut materials = vec![vec![Air; max_x + 1]; max_y + 2];
         for rock in rocks {
-            let coords: Result<Vec<(usize, usize)>, Self::Err> = rock
-                .split(" -> ")
-                .map(|coord_str| {
-                    let mut parts = coord_str.split(',');
-                    let x: usize = parts.next().map_or_else(
-                        || Err(eyre!("no x in {coord_str}")),
-                        |c| c.parse().context("reading x"),
-                    )?;
-                    let y: usize = parts.next().map_or_else(
-                        || Err(eyre!("no x in {coord_str}")),
-                        |c| c.parse().context("reading y"),
-                    )?;
-                    Ok((x, y))
-                })
-                .collect();
-
-            let mut previous = None;
-            for (x, y) in coords? {
-                if let Some((prev_x, prev_y)) = previous {
-                    if x == prev_x {
-                        for m in materials
-                            .iter_mut()
-                            .take(max(prev_y, y) + 1)
-                            .skip(min(prev_y, y))
-                        {
-                            m[x] = Rock;
-                        }
-                    } else if y == prev_y {
-                        for m in materials[y]
-                            .iter_mut()
-                            .take(max(prev_x, x) + 1)
-                            .skip(min(prev_x, x))
-                        {
-                            *m = Rock;
-                        }
-                    } else {
-                        panic!("no strait line from ({prev_x},{prev_y}) to ({x},{y})");
+            for ((prev_x, prev_y), (x, y)) in rock.into_iter().tuple_windows() {
+                let (prev_x, x) = (prev_x + shift, x + shift);
+                if x == prev_x {
+                    for m in materials
+                        .iter_mut()
+                        .take(max(prev_y, y) + 1)
+                        .skip(min(prev_y, y))
+                    {
+                        m[x] = Rock;
+                    }
+                } else {
+                    for m in materials[y]
+                        .iter_mut()
+                        .take(max(prev_x, x) + 1)
+                        .skip(min(prev_x, x))
+                    {
+                        *m = Rock;
                     }
                 }
-                previous = Some((x, y));
             }
         }
         Ok(Self {
             materials,
             max_x,
             max_y,
+            source: (500 + shift, 0),
             last_fall: vec![],
         })
     }
@@ -188,14 +185,16 @@ impl Scene {
     }
 }
 
-fn pour_sand(scene: &mut Scene, pouring_point: (usize, usize)) -> Vec<(usize, usize)> {
-    assert_eq!(
-        scene.get_material(pouring_point),
-        Air,
-        "Cannot pour sand in non Air"
-    );
+/// the length of the sand fall, kept as the scene last fall
+fn pour_sand(scene: &mut Scene, pouring_point: (usize, usize)) -> eyre::Result<usize> {
+    let material = scene.get_material(pouring_point);
+    if material != Air {
+        bail!("cannot pour sand on {material:?}");
+    }
     let (mut x, mut y) = pouring_point;
-    let mut fall = vec![(x, y)];
+    let mut fall = std::mem::take(&mut scene.last_fall);
+    fall.clear();
+    fall.push((x, y));
     while y <= scene.max_y {
         if Air == scene.get_material((x, y + 1)) {
             y += 1;
@@ -212,48 +211,67 @@ fn pour_sand(scene: &mut Scene, pouring_point: (usize, usize)) -> Vec<(usize, us
     }
     scene.set_material(Sand, (x, y));
 
-    scene.last_fall = fall.clone();
-    fall
+    scene.last_fall = fall;
+    Ok(scene.last_fall.len())
 }
 
-fn pour_max_sand_at_rest(input: &str) -> usize {
-    let mut scene: Scene = input.parse().unwrap();
-
-    let max_y = scene.max_y;
+fn count_sand_at_rest(mut scene: Scene) -> eyre::Result<usize> {
+    let (max_y, source) = (scene.max_y, scene.source);
     let mut count = 0;
-    while pour_sand(&mut scene, (500, 0)).len() <= max_y {
+    // the sand may pile up to the source when rocks are right below it
+    while scene.get_material(source) == Air && pour_sand(&mut scene, source)? <= max_y {
         count += 1;
     }
-    pour_sand(&mut scene, (500, 0));
-    // println!("{}", scene);
-    count
+    Ok(count)
 }
 
-fn pour_max_sand(input: &str) -> usize {
-    let mut scene: Scene = input.parse().unwrap();
-
+fn count_sand_until_blocked(mut scene: Scene) -> eyre::Result<usize> {
+    // each grain follows the previous one until the step before it came to rest
+    let mut path = vec![scene.source];
     let mut count = 0;
-    while scene.get_material((500, 0)) == Air {
-        pour_sand(&mut scene, (500, 0)).len();
+    while let Some(pouring_point) = path.pop() {
+        let len = pour_sand(&mut scene, pouring_point)?;
+        path.extend_from_slice(&scene.last_fall[..len - 1]);
         count += 1;
     }
 
-    count
+    Ok(count)
 }
 
-pub fn avoid_sand() {
-    let input = include_str!("../resources/day14_rocks.txt");
+pub fn pour_max_sand_at_rest(input: &str) -> eyre::Result<usize> {
+    count_sand_at_rest(input.parse()?)
+}
 
-    let sand_count = pour_max_sand_at_rest(input);
-    println!("max poured sand before falldown : {sand_count}");
+pub fn pour_max_sand(input: &str) -> eyre::Result<usize> {
+    count_sand_until_blocked(input.parse()?)
+}
+
+pub struct RockScan {
+    scene: Scene,
+}
 
-    let max_sand_count = pour_max_sand(input);
-    println!("max poured sand : {max_sand_count}");
+impl Solution<'_> for RockScan {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        Ok(Self {
+            scene: input.parse()?,
+        })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(count_sand_at_rest(self.scene.clone())?.into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(count_sand_until_blocked(self.scene.clone())?.into())
+    }
+    fn check(input: &str) -> Vec<eyre::Report> {
+        or_parse_error(check_lines(input, read_rock), || input.parse::<Scene>())
+    }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
 
     #[test]
     fn aoc_example_ok() {
@@ -263,7 +281,125 @@ mod tests {
         "};
         let scene: Scene = input.parse().unwrap();
         assert_eq!(9, scene.max_y);
-        assert_eq!(24, pour_max_sand_at_rest(input));
-        assert_eq!(93, pour_max_sand(input));
+        assert_eq!(24, pour_max_sand_at_rest(input).unwrap());
+        assert_eq!(93, pour_max_sand(input).unwrap());
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = pour_max_sand("498,4 -> 498,6\n503,4 -> 502,5\n").unwrap_err();
+        assert_eq!("line 2 : '503,4 -> 502,5'", error.to_string());
+        assert_eq!(
+            2,
+            RockScan::check("498,x\n498,4 -> 498,6\n503,4 -> 502,5\n").len()
+        );
+    }
+
+    #[test]
+    fn sand_can_pile_up_to_its_source() {
+        assert_eq!(1, pour_max_sand_at_rest("499,1 -> 501,1").unwrap());
+        assert_eq!(1, pour_max_sand("499,1 -> 501,1").unwrap());
+    }
+
+    #[test]
+    fn sand_can_spread_past_the_first_column() {
+        assert_eq!(0, pour_max_sand_at_rest("500,600 -> 501,600").unwrap());
+        assert_eq!(362402, pour_max_sand("500,600 -> 501,600").unwrap());
+    }
+
+    /// rock paths, as their corners, some of them deep enough for the sand to spread past x=0
+    fn rocks() -> impl Strategy<Value = Vec<Vec<(usize, usize)>>> {
+        let rock = (
+            (
+                490..510usize,
+                prop_oneof![15 => 1..12usize, 1 => 500..600usize],
+            ),
+            prop::collection::vec((any::<bool>(), -4..5isize), 1..4),
+        )
+            .prop_map(|(start, segments)| {
+                let mut corners = vec![start];
+                for (horizontal, len) in segments {
+                    let (x, y) = *corners.last().expect("a path has a start");
+                    corners.push(if horizontal {
+                        ((x as isize + len) as usize, y)
+                    } else {
+                        (x, (y as isize + len).max(1) as usize)
+                    });
+                }
+                corners
+            });
+        prop::collection::vec(rock, 1..5)
+    }
+
+    fn rock_points(rocks: &[Vec<(usize, usize)>]) -> HashSet<(isize, isize)> {
+        let mut points = HashSet::new();
+        for rock in rocks {
+            for ((x1, y1), (x2, y2)) in rock.iter().copied().tuple_windows() {
+                for x in x1.min(x2)..=x1.max(x2) {
+                    for y in y1.min(y2)..=y1.max(y2) {
+                        points.insert((x as isize, y as isize));
+                    }
+                }
+            }
+        }
+        points
+    }
+
+    /// grains of sand at rest before the first one falls below the rocks
+    fn pour(mut blocked: HashSet<(isize, isize)>) -> usize {
+        let max_y = blocked.iter().map(|(_, y)| *y).max().unwrap_or(0);
+        let mut count = 0;
+        while !blocked.contains(&(500, 0)) {
+            let (mut x, mut y) = (500, 0);
+            while let Some(next) = [(x, y + 1), (x - 1, y + 1), (x + 1, y + 1)]
+                .into_iter()
+                .find(|p| !blocked.contains(p))
+            {
+                (x, y) = next;
+                if y > max_y {
+                    return count;
+                }
+            }
+            blocked.insert((x, y));
+            count += 1;
+        }
+        count
+    }
+
+    /// grains of sand at rest with a floor : row by row, the sand reaches any air right below
+    /// or next to below the sand of the previous row
+    fn fill(blocked: &HashSet<(isize, isize)>) -> usize {
+        let max_y = blocked.iter().map(|(_, y)| *y).max().unwrap_or(0);
+        let (left, width) = (500 - max_y - 2, 2 * max_y as usize + 5);
+        let mut rows = vec![vec![false; width]; max_y as usize + 2];
+        // rocks out of the sand reach are left out
+        for (x, y) in blocked {
+            if let Some(rock) = usize::try_from(x - left).ok().filter(|i| *i < width) {
+                rows[*y as usize][rock] = true;
+            }
+        }
+        let mut sand = vec![false; width];
+        sand[(500 - left) as usize] = true;
+        let mut count = 1;
+        for rocks in &rows[1..] {
+            sand = (0..width)
+                .map(|i| !rocks[i] && sand[i.saturating_sub(1)..(i + 2).min(width)].contains(&true))
+                .collect();
+            count += sand.iter().filter(|s| **s).count();
+        }
+        count
+    }
+
+    proptest! {
+        #[test]
+        fn rock_scan_can_be_read(rocks in rocks()) {
+            let scan = rocks
+                .iter()
+                .map(|rock| rock.iter().map(|(x, y)| format!("{x},{y}")).join(" -> "))
+                .join("\n");
+            let blocked = rock_points(&rocks);
+            prop_assert_eq!(fill(&blocked), pour_max_sand(&scan).unwrap());
+            prop_assert_eq!(pour(blocked), pour_max_sand_at_rest(&scan).unwrap());
+        }
     }
 }
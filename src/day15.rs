@@ -1,15 +1,17 @@
-use eyre::{eyre, Context};
+use crate::parsing::{check_lines, or_parse_error, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, Context, ContextCompat};
 use std::collections::HashSet;
 use std::str::FromStr;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct Point {
+pub struct Point {
     x: isize,
     y: isize,
 }
 
 impl Point {
-    fn distance(&self, other: &Point) -> isize {
+    pub fn distance(&self, other: &Point) -> isize {
         (other.x - self.x).abs() + (other.y - self.y).abs()
     }
 }
@@ -19,53 +21,46 @@ impl FromStr for Point {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // "x=231045, y=2977983"
-        let mut parts = s.split(", ").map(|p| &p[2..]);
-        let x: isize = parts
-            .next()
+        let (x, y) = s.split_once(", ").context("no ', ' between x and y")?;
+        let x: isize = x
+            .strip_prefix("x=")
             .map_or_else(|| Err(eyre!("no x")), |p| p.parse().context("reading x"))?;
-        let y: isize = parts
-            .next()
+        let y: isize = y
+            .strip_prefix("y=")
             .map_or_else(|| Err(eyre!("no y")), |p| p.parse().context("reading y"))?;
         Ok(Self { x, y })
     }
 }
 
-fn read_pos(input: &str) -> Vec<(Point, Point)> {
-    input
-        .lines()
-        .map(|l| {
-            let sensor: Point = l["Sensor at ".len()..l.find(':').expect("':' is mandatory")]
-                .parse()
-                .expect("sensor parse error");
-            let beacon: Point = l[("closest beacon is at ".len()
-                + l.find("closest beacon is at ").expect("no beacon part"))..]
-                .parse()
-                .expect("beacon parse error");
-            (sensor, beacon)
-        })
-        .collect()
+fn read_sensor(line: &str) -> eyre::Result<(Point, Point)> {
+    let (sensor, beacon) = line
+        .strip_prefix("Sensor at ")
+        .and_then(|l| l.split_once(": closest beacon is at "))
+        .context("expecting 'Sensor at ...: closest beacon is at ...'")?;
+    Ok((
+        sensor.parse().context("reading sensor")?,
+        beacon.trim_end().parse().context("reading beacon")?,
+    ))
 }
 
-fn count_impossible_sport(input: &str, y: isize) -> usize {
-    let sensors_beacons = read_pos(input);
+pub fn read_pos(input: &str) -> eyre::Result<Vec<(Point, Point)>> {
+    let sensors_beacons = parse_lines(input, read_sensor)?;
+    if sensors_beacons.is_empty() {
+        bail!("no sensor in report");
+    }
+    Ok(sensors_beacons)
+}
 
+fn count_impossible_spots(sensors_beacons: &[(Point, Point)], y: isize) -> usize {
     let beacons: HashSet<Point> = sensors_beacons.iter().map(|(_, b)| b).copied().collect();
 
     let max_dist = sensors_beacons
         .iter()
         .map(|(s, b)| s.distance(b))
         .max()
-        .expect("no max distance");
-    let min_x = sensors_beacons
-        .iter()
-        .map(|(s, _)| s.x)
-        .min()
-        .expect("no max distance");
-    let max_x = sensors_beacons
-        .iter()
-        .map(|(s, _)| s.x)
-        .max()
-        .expect("no max distance");
+        .unwrap_or(0);
+    let min_x = sensors_beacons.iter().map(|(s, _)| s.x).min().unwrap_or(0);
+    let max_x = sensors_beacons.iter().map(|(s, _)| s.x).max().unwrap_or(0);
 
     (min_x - max_dist..=max_x + max_dist)
         .filter(|x| {
@@ -78,18 +73,15 @@ fn count_impossible_sport(input: &str, y: isize) -> usize {
         .count()
 }
 
-pub fn find_beacons() {
-    let input = include_str!("../resources/day15_sensors_beacons.txt");
-    let count = count_impossible_sport(input, 2000000);
-    println!("number of impossible spots at 2000000 : {count}");
-
-    let freq = find_beacon(input, 4000000, 4000000).unwrap();
-
-    println!("beacon freq : {freq}");
+pub fn count_impossible_sport(input: &str, y: isize) -> eyre::Result<usize> {
+    Ok(count_impossible_spots(&read_pos(input)?, y))
 }
 
-pub fn find_beacon(input: &str, max_x: isize, max_y: isize) -> Option<isize> {
-    let sensors_beacons = read_pos(input);
+fn find_distress_beacon(
+    sensors_beacons: &[(Point, Point)],
+    max_x: isize,
+    max_y: isize,
+) -> eyre::Result<isize> {
     let sensors_dist: Vec<(Point, isize)> = sensors_beacons
         .iter()
         .map(|(s, b)| (*s, s.distance(b)))
@@ -101,21 +93,49 @@ pub fn find_beacon(input: &str, max_x: isize, max_y: isize) -> Option<isize> {
                 .iter()
                 .map(|(s, d)| *d - Point { x, y }.distance(s))
                 .max()
-                .unwrap();
+                .unwrap_or(-1);
 
             if margin < 0 {
-                return Some(x * 4000000 + y);
+                return Ok(x * 4000000 + y);
             }
             x += margin + 1;
         }
     }
-    None
+    Err(eyre!(
+        "every spot up to ({max_x},{max_y}) is covered by a sensor"
+    ))
+}
+
+pub fn find_beacon(input: &str, max_x: isize, max_y: isize) -> eyre::Result<isize> {
+    find_distress_beacon(&read_pos(input)?, max_x, max_y)
+}
+
+pub struct SensorsReport {
+    sensors_beacons: Vec<(Point, Point)>,
+}
+
+impl Solution<'_> for SensorsReport {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        Ok(Self {
+            sensors_beacons: read_pos(input)?,
+        })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(count_impossible_spots(&self.sensors_beacons, 2000000).into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(find_distress_beacon(&self.sensors_beacons, 4000000, 4000000)?.into())
+    }
+    fn check(input: &str) -> Vec<eyre::Report> {
+        or_parse_error(check_lines(input, read_sensor), || read_pos(input))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn aoc_examples_ok() {
@@ -135,7 +155,41 @@ mod tests {
             Sensor at x=14, y=3: closest beacon is at x=15, y=3
             Sensor at x=20, y=1: closest beacon is at x=15, y=3
         "};
-        assert_eq!(26, count_impossible_sport(input, 10));
+        assert_eq!(26, count_impossible_sport(input, 10).unwrap());
         assert_eq!(56000011, find_beacon(input, 20, 20).unwrap());
     }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = read_pos("Sensor at x=2, y=18: closest beacon is at x=-2 y=15").unwrap_err();
+        assert!(error.to_string().starts_with("line 1 : "));
+    }
+
+    fn point() -> impl Strategy<Value = Point> {
+        (0..20isize, 0..20isize).prop_map(|(x, y)| Point { x, y })
+    }
+
+    proptest! {
+        #[test]
+        fn sensors_can_be_read(sensors_beacons in prop::collection::vec((point(), point()), 1..6), row in 0..20isize) {
+            let report = sensors_beacons
+                .iter()
+                .map(|(s, b)| format!("Sensor at x={}, y={}: closest beacon is at x={}, y={}\n", s.x, s.y, b.x, b.y))
+                .collect::<String>();
+            prop_assert_eq!(&sensors_beacons, &read_pos(&report).unwrap());
+
+            let covered = |p: &Point| sensors_beacons.iter().any(|(s, b)| s.distance(p) <= s.distance(b));
+            let impossible = (-60..80)
+                .map(|x| Point { x, y: row })
+                .filter(|p| covered(p) && sensors_beacons.iter().all(|(_, b)| b != p))
+                .count();
+            prop_assert_eq!(impossible, count_impossible_sport(&report, row).unwrap());
+
+            let distress = (0..20)
+                .flat_map(|y| (0..20).map(move |x| Point { x, y }))
+                .find(|p| !covered(p))
+                .map(|p| p.x * 4000000 + p.y);
+            prop_assert_eq!(distress, find_beacon(&report, 19, 19).ok());
+        }
+    }
 }
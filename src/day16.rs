@@ -1,4 +1,6 @@
-use eyre::{Context, ContextCompat};
+use crate::parsing::{check_lines, or_parse_error, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, Context, ContextCompat};
 use itertools::Itertools;
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
@@ -13,15 +15,17 @@ struct Valve<'v> {
 
 impl<'v> Valve<'v> {
     fn from_str(s: &'v str) -> Result<Self, eyre::Error> {
-        // Valve EF
-        let name = &s[6..8];
-        let flow: usize = s[s.find('=').unwrap() + 1..s.find(';').unwrap()]
-            .parse()
-            .context("reading flow")?;
-        let nexts: Vec<_> = s[s
-            .find("to valve")
-            .with_context(|| format!("reading '{s}'"))?
-            + 9..]
+        // Valve EF has flow rate=0; tunnels lead to valves DD, II, BB
+        let (name, flow) = s
+            .strip_prefix("Valve ")
+            .and_then(|s| s.split_once(" has flow rate="))
+            .context("expecting 'Valve XX has flow rate=N'")?;
+        let (flow, nexts) = flow.split_once(';').context("no ';' after flow rate")?;
+        let flow: usize = flow.parse().context("reading flow")?;
+        let nexts: Vec<_> = ["tunnels lead to valves ", "tunnel leads to valve "]
+            .into_iter()
+            .find_map(|prefix| nexts.trim().strip_prefix(prefix))
+            .with_context(|| format!("reading tunnels from '{nexts}'"))?
             .split(',')
             .map(str::trim)
             .collect();
@@ -76,22 +80,34 @@ impl<'v> OpeningPath<'v> {
 }
 
 #[derive(Debug, Clone)]
-struct Volcano<'v> {
+pub struct Volcano<'v> {
     valves_by_name: HashMap<&'v str, Valve<'v>>,
     time_left: usize,
 }
 impl<'v> Volcano<'v> {
     fn from_str(s: &'v str) -> Result<Self, eyre::Error> {
-        let valves = s.lines().filter_map(|l| match Valve::from_str(l) {
-            Ok(v) => Some(v),
-            Err(e) => {
-                println!("error {e}");
-                None
+        let mut valves_by_name = HashMap::new();
+        for valve in parse_lines(s, Valve::from_str)? {
+            if valves_by_name.contains_key(valve.name) {
+                bail!("valve {} is described twice", valve.name);
             }
-        });
+            valves_by_name.insert(valve.name, valve);
+        }
+        if !valves_by_name.contains_key("AA") {
+            bail!("no starting valve AA");
+        }
+        for valve in valves_by_name.values() {
+            if let Some(unknown) = valve
+                .nexts
+                .iter()
+                .find(|n| !valves_by_name.contains_key(*n))
+            {
+                bail!("valve {} leads to unknown valve {unknown}", valve.name);
+            }
+        }
 
         Ok(Self {
-            valves_by_name: valves.map(|v| (v.name, v)).collect(),
+            valves_by_name,
             time_left: 30,
         })
     }
@@ -131,7 +147,7 @@ impl<'v> Volcano<'v> {
         result
     }
 
-    fn max_score_optimized(&self) -> usize {
+    pub fn max_score_optimized(&self) -> usize {
         const MAX_POP: usize = 100;
         let distances_by_edges = self.distances();
 
@@ -174,10 +190,6 @@ impl<'v> Volcano<'v> {
                     let local_best_score = local_best.score(self);
 
                     if local_best_score > max_score {
-                        // println!(
-                        //     "new best : {:?} => {local_best_score}",
-                        //     new_bests.first().unwrap()
-                        // );
                         max_score = local_best_score;
                     }
                 }
@@ -188,7 +200,7 @@ impl<'v> Volcano<'v> {
         max_score
     }
 
-    fn max_dual_score_optimized(&self) -> usize {
+    pub fn max_dual_score_optimized(&self) -> usize {
         const MAX_POP: usize = 300;
         let distances_by_edges = self.distances();
 
@@ -258,10 +270,6 @@ impl<'v> Volcano<'v> {
                     let local_best_score = local_best1.dual_score(local_best2, self);
 
                     if local_best_score > max_score {
-                        // println!(
-                        //     "new best : {:?} => {local_best_score}",
-                        //     new_bests.first().unwrap()
-                        // );
                         max_score = local_best_score;
                     }
                 }
@@ -273,21 +281,82 @@ impl<'v> Volcano<'v> {
     }
 }
 
-pub fn escape_volcano() {
-    let input = include_str!("../resources/day16_volcano.txt");
-    let mut volcano = Volcano::from_str(input).unwrap();
-    let max_pressure = volcano.max_score_optimized();
-    println!("max pressure {max_pressure}");
-
-    volcano.time_left = 26;
-    let max_pressure = volcano.max_dual_score_optimized();
-    println!("max dual pressure {max_pressure}");
+impl<'v> Solution<'v> for Volcano<'v> {
+    fn parse(input: &'v str) -> eyre::Result<Self> {
+        Volcano::from_str(input)
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(self.max_score_optimized().into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        let mut volcano = self.clone();
+        volcano.time_left = 26;
+        Ok(volcano.max_dual_score_optimized().into())
+    }
+    fn check(input: &'v str) -> Vec<eyre::Report> {
+        or_parse_error(check_lines(input, Valve::from_str), || {
+            Volcano::from_str(input)
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
+
+    /// AA, BB, CC...
+    fn valve_name(i: usize) -> String {
+        char::from(b'A' + i as u8).to_string().repeat(2)
+    }
+
+    /// flows and tunnels (as indexes) of each valve, and the scan reporting them
+    fn valve_scan() -> impl Strategy<Value = (Vec<usize>, Vec<Vec<usize>>, String)> {
+        (1..8usize)
+            .prop_flat_map(|n| {
+                (
+                    prop::collection::vec(0..30usize, n),
+                    prop::collection::vec(prop::collection::vec(0..n, 1..4), n),
+                )
+            })
+            .prop_map(|(flows, tunnels)| {
+                let scan = flows
+                    .iter()
+                    .zip(&tunnels)
+                    .enumerate()
+                    .map(|(i, (flow, nexts))| {
+                        let names = nexts.iter().map(|n| valve_name(*n)).join(", ");
+                        let tunnels = match nexts.len() {
+                            1 => format!("tunnel leads to valve {names}"),
+                            _ => format!("tunnels lead to valves {names}"),
+                        };
+                        format!("Valve {} has flow rate={flow}; {tunnels}", valve_name(i))
+                    })
+                    .join("\n");
+                (flows, tunnels, scan)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn valve_scan_can_be_read((flows, tunnels, scan) in valve_scan()) {
+            let volcano = Volcano::from_str(&scan).unwrap();
+            prop_assert_eq!(flows.len(), volcano.valves_by_name.len());
+            for (i, (flow, nexts)) in flows.iter().zip(&tunnels).enumerate() {
+                let valve = &volcano.valves_by_name[valve_name(i).as_str()];
+                prop_assert_eq!(*flow, valve.flow);
+                prop_assert_eq!(nexts.iter().map(|n| valve_name(*n)).collect_vec(), valve.nexts.clone());
+            }
+            // no valve can be opened before the first minute
+            prop_assert!(volcano.max_score_optimized() <= 29 * flows.iter().sum::<usize>());
+        }
+
+        #[test]
+        fn valve_parsing_does_not_panic(s in "(Valve [A-C]{2} has flow rate=[0-9]{1,2}; tunnels? leads? to valves? [A-C]{2}(, [A-C]{2})?\n?){0,4}") {
+            let _ = Volcano::from_str(&s);
+        }
+    }
 
     #[test]
     fn aoc_example_work() {
@@ -322,4 +391,14 @@ mod tests {
         volcano.time_left = 26;
         assert_eq!(1707, volcano.max_dual_score_optimized());
     }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let unknown_valve = indoc! {"
+            Valve AA has flow rate=0; tunnels lead to valves BB, ZZ
+            Valve BB has flow rate=13; tunnel leads to valve AA
+        "};
+        let error = Volcano::from_str(unknown_valve).unwrap_err();
+        assert_eq!("valve AA leads to unknown valve ZZ", error.to_string());
+    }
 }
@@ -1,5 +1,7 @@
 use crate::day17::Move::{Left, Right};
-use eyre::eyre;
+use crate::parsing::{check_chars, or_parse_error};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, WrapErr};
 use std::collections::VecDeque;
 use std::fmt::{Display, Formatter, Write};
 use std::str::FromStr;
@@ -58,8 +60,8 @@ impl FromStr for Move {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.chars().next() {
-            Some(l) if l == '<' => Ok(Left),
-            Some(r) if r == '>' => Ok(Right),
+            Some('<') => Ok(Left),
+            Some('>') => Ok(Right),
             _ => Err(eyre!("cannot parse {s} as Move")),
         }
     }
@@ -73,7 +75,7 @@ struct TetrisPiece<'t> {
 }
 
 #[derive(Debug, Clone)]
-struct Cave {
+pub struct Cave {
     rocks: VecDeque<[bool; 7]>,
     moves: Vec<Move>,
     offset: usize,
@@ -83,9 +85,21 @@ impl FromStr for Cave {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let moves: Result<Vec<Move>, _> = s.chars().map(|c| c.to_string().parse()).collect();
+        let moves: Vec<Move> = s
+            .trim_end()
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                c.to_string()
+                    .parse()
+                    .wrap_err_with(|| format!("at column {}", i + 1))
+            })
+            .collect::<eyre::Result<_>>()?;
+        if moves.is_empty() {
+            bail!("no jet of hot gas");
+        }
         Ok(Self {
-            moves: moves?,
+            moves,
             rocks: VecDeque::from(vec![]),
             offset: 0,
         })
@@ -143,7 +157,6 @@ impl Cave {
         true
     }
     fn freeze_piece(&mut self, piece: TetrisPiece) {
-        // println!("freeze piece : {piece:?}");
         let max_y = (piece.pos.y + piece.rocks.iter().map(|r| r.y).max().unwrap_or(0)) as usize;
         for _ in self.rocks.len()..=max_y {
             self.rocks.push_back([false; 7])
@@ -153,7 +166,7 @@ impl Cave {
         }
     }
 
-    fn stack_pieces(mut self, limit: usize) -> usize {
+    pub fn stack_pieces(mut self, limit: usize) -> usize {
         let mut move_index = 0usize;
         let moves_mod = self.moves.len();
         let mut piece_index = 0usize;
@@ -165,7 +178,6 @@ impl Cave {
         let mut i = 0;
 
         while i < limit {
-            // println!("i {i}");
             let pos = Point {
                 x: 2,
                 y: self.rocks.len() as isize + 3,
@@ -207,9 +219,9 @@ impl Cave {
                     {
                         period_found = true;
                         let period = i - j;
-                        // println!("period checked against {} values : {reason}", proof_len);
 
-                        let skip_runs: usize = (limit - i) / period;
+                        // the pieces left once this one is stacked
+                        let skip_runs: usize = (limit - i - 1) / period;
                         i += skip_runs * period;
                         self.offset += (height - h) * (skip_runs);
                         break 'search;
@@ -218,24 +230,30 @@ impl Cave {
             }
             i += 1;
         }
-        // println!("{}", self);
         self.offset + self.rocks.len()
     }
 }
 
-pub fn tetris_rock() {
-    let input = include_str!("../resources/day17_moves.txt");
-    let cave: Cave = input.parse().unwrap();
-
-    let height = cave.clone().stack_pieces(2022);
-    println!("height after 2022 rocks {height}");
-    let height = cave.stack_pieces(1000000000000);
-    println!("height after 1000000000000 rocks {height}");
+impl Solution<'_> for Cave {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        input.parse()
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(self.clone().stack_pieces(2022).into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(self.clone().stack_pieces(1000000000000).into())
+    }
+    fn check(input: &str) -> Vec<eyre::Report> {
+        or_parse_error(check_chars(input, "<>"), || input.parse::<Self>())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use itertools::Itertools;
+    use proptest::prelude::*;
 
     #[test]
     fn aoc_example_works() {
@@ -246,4 +264,32 @@ mod tests {
         assert_eq!(3068, cave.clone().stack_pieces(2022));
         assert_eq!(1514285714288, cave.clone().stack_pieces(1000000000000));
     }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = ">><=<".parse::<Cave>().unwrap_err();
+        assert_eq!("at column 4", error.to_string());
+        assert!("\n".parse::<Cave>().is_err());
+    }
+
+    #[test]
+    fn periods_stop_at_the_limit() {
+        let cave: Cave = ">><<<".parse().unwrap();
+        // a period of 5 pieces is found once 35 pieces are stacked
+        assert_eq!(96, cave.clone().stack_pieces(39));
+        assert_eq!(98, cave.stack_pieces(40));
+    }
+
+    proptest! {
+        #[test]
+        fn jets_can_be_read(jets in "[<>]{1,40}") {
+            let cave: Cave = jets.parse().unwrap();
+            let heights = (0..40).map(|n| cave.clone().stack_pieces(n)).collect_vec();
+            prop_assert_eq!(0, heights[0]);
+            // a piece is at most 4 rows tall, and may fall beside the highest rocks
+            for (previous, height) in heights.iter().tuple_windows() {
+                prop_assert!((*previous..=*previous + 4).contains(height));
+            }
+        }
+    }
 }
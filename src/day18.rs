@@ -1,4 +1,6 @@
-use eyre::eyre;
+use crate::parsing::{check_lines, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::{eyre, WrapErr};
 use itertools::Itertools;
 use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
@@ -6,7 +8,7 @@ use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-struct Point3d {
+pub struct Point3d {
     x: isize,
     y: isize,
     z: isize,
@@ -15,10 +17,15 @@ impl FromStr for Point3d {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some((x, y, z)) = s.split(',').filter_map(|v| v.parse().ok()).collect_tuple() {
+        let coords: Vec<isize> = s
+            .trim()
+            .split(',')
+            .map(|v| v.trim().parse().wrap_err_with(|| format!("reading '{v}'")))
+            .collect::<eyre::Result<_>>()?;
+        if let Some((x, y, z)) = coords.into_iter().collect_tuple() {
             return Ok(Self { x, y, z });
         }
-        Err(eyre!("parse error on {s}"))
+        Err(eyre!("expecting 3 coordinates in {s}"))
     }
 }
 impl Display for Point3d {
@@ -75,7 +82,7 @@ fn count_faces(points: &[Point3d]) -> HashMap<Point3d, usize> {
     count_by_faces
 }
 
-fn count_free_faces(points: &[Point3d]) -> usize {
+pub fn count_free_faces(points: &[Point3d]) -> usize {
     let count_by_faces = count_faces(points);
 
     count_by_faces
@@ -84,7 +91,7 @@ fn count_free_faces(points: &[Point3d]) -> usize {
         .count()
 }
 
-fn count_reachable_free_faces(points: &[Point3d]) -> usize {
+pub fn count_reachable_free_faces(points: &[Point3d]) -> usize {
     let material_points: HashSet<&Point3d> = points.iter().collect();
 
     let (min_coord, max_coord) = points
@@ -93,7 +100,7 @@ fn count_reachable_free_faces(points: &[Point3d]) -> usize {
         .reduce(|(min_c, max_c), (local_min, local_max)| {
             (min(min_c, local_min), max(max_c, local_max))
         })
-        .unwrap();
+        .unwrap_or((0, 0));
 
     // let's flood a cube containing all points
     let cube_edge = (max_coord - min_coord + 2) as usize;
@@ -181,20 +188,30 @@ fn count_reachable_free_faces(points: &[Point3d]) -> usize {
         .count()
 }
 
-pub fn observe_boulders() {
-    let droplets: Vec<Point3d> = include_str!("../resources/day18_droplets.txt")
-        .lines()
-        .filter_map(|l| l.parse().ok())
-        .collect();
-    let free_faces_count = count_free_faces(&droplets);
-    println!("free faces : {free_faces_count}");
-    let reachable_free_faces_count = count_reachable_free_faces(&droplets);
-    println!("reachable free faces : {reachable_free_faces_count}");
+pub struct Droplet {
+    cubes: Vec<Point3d>,
+}
+
+impl Solution<'_> for Droplet {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        let cubes = parse_lines(input, str::parse)?;
+        Ok(Self { cubes })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(count_free_faces(&self.cubes).into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(count_reachable_free_faces(&self.cubes).into())
+    }
+    fn check(input: &str) -> Vec<eyre::Report> {
+        check_lines(input, str::parse::<Point3d>)
+    }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn aoc_example_works() {
@@ -213,8 +230,55 @@ mod tests {
             2,1,5
             2,3,5
         "};
-        let points: Vec<_> = input.lines().filter_map(|l| l.parse().ok()).collect();
+        let points: Vec<Point3d> = parse_lines(input, str::parse).unwrap();
         assert_eq!(64, count_free_faces(&points));
         assert_eq!(58, count_reachable_free_faces(&points));
     }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        assert!("1,x,2,3".parse::<Point3d>().is_err());
+        assert!("1,2".parse::<Point3d>().is_err());
+        let error = Droplet::parse("2,2,2\n1,2,2,\n").err().unwrap();
+        assert_eq!("line 2 : '1,2,2,'", error.to_string());
+    }
+
+    fn cubes() -> impl Strategy<Value = HashSet<(isize, isize, isize)>> {
+        prop::collection::hash_set((0..5isize, 0..5isize, 0..5isize), 1..40)
+    }
+
+    proptest! {
+        #[test]
+        fn droplet_can_be_read(cubes in cubes()) {
+            let scan = cubes.iter().map(|(x, y, z)| format!("{x},{y},{z}\n")).collect::<String>();
+            let points: Vec<Point3d> = parse_lines(&scan, str::parse).unwrap();
+            let neighbours = |(x, y, z): (isize, isize, isize)| {
+                [(x - 1, y, z), (x + 1, y, z), (x, y - 1, z), (x, y + 1, z), (x, y, z - 1), (x, y, z + 1)]
+            };
+            let free = cubes
+                .iter()
+                .flat_map(|c| neighbours(*c))
+                .filter(|n| !cubes.contains(n))
+                .count();
+            prop_assert_eq!(free, count_free_faces(&points));
+
+            // water flooding around the droplet, from a corner of its bounding box
+            let outside = |(x, y, z): (isize, isize, isize)| [x, y, z].iter().all(|c| (-1..=5).contains(c));
+            let mut water = HashSet::from([(-1, -1, -1)]);
+            let mut flood = vec![(-1, -1, -1)];
+            while let Some(w) = flood.pop() {
+                for n in neighbours(w) {
+                    if outside(n) && !cubes.contains(&n) && water.insert(n) {
+                        flood.push(n);
+                    }
+                }
+            }
+            let reachable = cubes
+                .iter()
+                .flat_map(|c| neighbours(*c))
+                .filter(|n| water.contains(n))
+                .count();
+            prop_assert_eq!(reachable, count_reachable_free_faces(&points));
+        }
+    }
 }
@@ -1,8 +1,10 @@
 use crate::day19::Material::{Clay, Geode, Obsidian, Ore};
+use crate::parsing::{check_lines, parse_lines};
+use crate::solution::{Answer, Solution};
 use eyre::{eyre, ContextCompat, WrapErr};
 use itertools::Itertools;
 use rayon::prelude::*;
-use std::cmp::min;
+use std::cmp::{max, min};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter, Write};
 use std::ops::{Index, IndexMut};
@@ -70,7 +72,7 @@ impl IndexMut<usize> for MatQuantities {
 }
 
 #[derive(Debug)]
-struct Blueprint {
+pub struct Blueprint {
     id: usize,
     cost_by_robot: [MatQuantities; MATERIAL_COUNT],
 }
@@ -102,7 +104,8 @@ impl FromStr for Blueprint {
         let id = lines.next().map_or_else(
             || Err(eyre!("No blueprint id line in '{s}'")),
             |line| {
-                line["Blueprint ".len()..]
+                line.strip_prefix("Blueprint ")
+                    .with_context(|| eyre!("no 'Blueprint ' prefix in {line}"))?
                     .parse()
                     .with_context(|| eyre!("reading BP id from {line}"))
             },
@@ -137,7 +140,6 @@ impl<'f> Production<'f> {
         // - (obsidian/geode ratio * available)*(left_rounds - 1) + (obsidian/geode ratio * productible obs)*(left_rounds - 2)
         // ...
         (0..MATERIAL_COUNT)
-            .into_iter()
             .map(|m_idx| {
                 //  RELATIVE_COST             EXTRACTED              PRODUCTION_SPEED
                 relative_costs[m_idx]
@@ -233,7 +235,7 @@ impl Blueprint {
         costs
     }
 
-    fn get_score(&self, rounds: usize) -> usize {
+    pub fn get_score(&self, rounds: usize) -> usize {
         let costs = self.costs();
         let mut bests = vec![Production {
             extracted: Default::default(),
@@ -246,7 +248,7 @@ impl Blueprint {
             factory: self,
         }];
 
-        let max_pop = (costs[costs.0.len() - 1] / 70).pow(2);
+        let max_pop = max(1, (costs[costs.0.len() - 1] / 70).pow(2));
 
         for i in 0..rounds {
             let max_pop = max_pop
@@ -262,15 +264,15 @@ impl Blueprint {
         bests.sort_by_key(|b| std::cmp::Reverse(b.score(&costs)));
         debug_assert_eq!(
             0,
-            bests.get(0).unwrap().rounds_left,
+            bests.first().unwrap().rounds_left,
             "all rounds should have been run"
         );
 
-        let best = bests.get(0).unwrap();
+        let best = bests.first().unwrap();
 
         best.extracted[Geode as usize]
     }
-    fn get_quality(&self, rounds: usize) -> usize {
+    pub fn get_quality(&self, rounds: usize) -> usize {
         self.id * self.get_score(rounds)
     }
 }
@@ -285,30 +287,73 @@ impl Display for Blueprint {
     }
 }
 
-pub fn collect_geodes() {
-    let blueprints: Vec<Blueprint> = include_str!("../resources/day19_blueprints.txt")
-        .lines()
-        .filter_map(|l| l.parse().ok())
-        .collect();
-    // println!(
-    //     "{} blueprints : {}",
-    //     blueprints.len(),
-    //     blueprints.iter().map(|bp| bp.id).join(",")
-    // );
-
-    let quality_sum: usize = blueprints.par_iter().map(|b| b.get_quality(24)).sum();
-    println!("quality_sum : {quality_sum}");
-
-    let geodes_product: usize = blueprints[..3]
-        .par_iter()
-        .map(|bp| bp.get_score(32))
-        .product();
-    println!("geodes_product : {geodes_product}");
+pub struct Blueprints {
+    blueprints: Vec<Blueprint>,
+}
+
+impl Solution<'_> for Blueprints {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        let blueprints = parse_lines(input, str::parse)?;
+        Ok(Self { blueprints })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        let quality_sum: usize = self.blueprints.par_iter().map(|b| b.get_quality(24)).sum();
+        Ok(quality_sum.into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        let geodes_product: usize = self.blueprints[..min(3, self.blueprints.len())]
+            .par_iter()
+            .map(|bp| bp.get_score(32))
+            .product();
+        Ok(geodes_product.into())
+    }
+    fn check(input: &str) -> Vec<eyre::Report> {
+        check_lines(input, str::parse::<Blueprint>)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    /// costs of the ore, clay, obsidian and geode robots, and the blueprint line listing them
+    fn blueprint_line() -> impl Strategy<Value = ([[usize; MATERIAL_COUNT]; MATERIAL_COUNT], String)>
+    {
+        (1..50usize, [1..5usize, 1..5, 1..5, 1..20, 1..5, 1..20]).prop_map(
+            |(id, [ore, clay, obsidian_ore, obsidian_clay, geode_ore, geode_obsidian])| {
+                let line = format!(
+                    "Blueprint {id}: Each ore robot costs {ore} ore. \
+                     Each clay robot costs {clay} ore. \
+                     Each obsidian robot costs {obsidian_ore} ore and {obsidian_clay} clay. \
+                     Each geode robot costs {geode_ore} ore and {geode_obsidian} obsidian."
+                );
+                let costs = [
+                    [ore, 0, 0, 0],
+                    [clay, 0, 0, 0],
+                    [obsidian_ore, obsidian_clay, 0, 0],
+                    [geode_ore, 0, geode_obsidian, 0],
+                ];
+                (costs, line)
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn blueprint_can_be_read((costs, line) in blueprint_line()) {
+            let blueprint: Blueprint = line.parse().unwrap();
+            prop_assert_eq!(costs, blueprint.cost_by_robot.map(|c| c.0));
+            // at best, a new geode robot every minute from the second one
+            prop_assert!(blueprint.get_score(8) <= (0..8).sum());
+        }
+
+        #[test]
+        fn blueprint_parsing_does_not_panic(s in "Blueprint [0-9]: (Each [a-z]{2,4} robot costs [0-9] [a-z]{2,4}( and [0-9] clay)?\\. ?){0,4}") {
+            let _ = s.parse::<Blueprint>();
+        }
+    }
+
     #[test]
     fn aoc_example_works() {
         let input="Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.";
@@ -322,4 +367,14 @@ mod tests {
         assert_eq!(56, bp.get_score(32));
         assert_eq!(62, bp2.get_score(32));
     }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        assert!("Blueprint one: Each ore robot costs 4 ore."
+            .parse::<Blueprint>()
+            .is_err());
+        assert!("Blueprint 1: Each ore robot costs 4 gold."
+            .parse::<Blueprint>()
+            .is_err());
+    }
 }
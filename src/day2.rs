@@ -1,89 +1,252 @@
-use eyre::{eyre, ContextCompat};
+use crate::parsing::{check_lines, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, ContextCompat};
 use std::str::FromStr;
+use std::sync::LazyLock;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-enum Move {
-    Rock,
-    Paper,
-    Scissors,
+/// a move, as its index in the moves of some [Rules]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Move(pub usize);
+
+impl Move {
+    pub const ROCK: Move = Move(0);
+    pub const PAPER: Move = Move(1);
+    pub const SCISSORS: Move = Move(2);
+    /// only in [Rules::rock_paper_scissors_lizard_spock]
+    pub const SPOCK: Move = Move(3);
+    pub const LIZARD: Move = Move(4);
+}
+
+/// how a move is named, written in the guide, and scored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveRule {
+    pub name: String,
+    /// symbol in the first column, for the opponent
+    pub opponent: char,
+    /// symbol in the second column, for you
+    pub response: char,
+    pub score: usize,
 }
 
-fn move_score(mv: Move) -> usize {
-    match mv {
-        Move::Rock => 1,
-        Move::Paper => 2,
-        Move::Scissors => 3,
+impl MoveRule {
+    pub fn new(name: &str, opponent: char, response: char, score: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            opponent,
+            response,
+            score,
+        }
     }
 }
 
-const LOSE_SCORE: usize = 0;
-const DRAW_SCORE: usize = 3;
-const WIN_SCORE: usize = 6;
+pub const LOSE_SCORE: usize = 0;
+pub const DRAW_SCORE: usize = 3;
+pub const WIN_SCORE: usize = 6;
 
-fn round_score(other_move: Move, your_move: Move) -> usize {
-    if other_move == your_move {
-        return DRAW_SCORE + move_score(your_move);
-    }
-    move_score(your_move)
-        + match your_move {
-            Move::Rock => {
-                if other_move == Move::Scissors {
-                    WIN_SCORE
-                } else {
-                    LOSE_SCORE
-                }
+/// a game like Rock Paper Scissors : its moves, which move beats which, and scores
+#[derive(Debug, Clone)]
+pub struct Rules {
+    moves: Vec<MoveRule>,
+    /// `beats[a][b]` when playing `a` wins against `b`
+    beats: Vec<Vec<bool>>,
+    lose_score: usize,
+    draw_score: usize,
+    win_score: usize,
+}
+
+impl Rules {
+    /// `beats` lists (winner, loser) pairs of move indexes
+    ///
+    /// every move has to beat another one and to be beaten by another one,
+    /// so that any strategy can be followed
+    pub fn new(moves: Vec<MoveRule>, beats: &[(usize, usize)]) -> eyre::Result<Self> {
+        for (i, m) in moves.iter().enumerate() {
+            if let Some(other) = moves[..i]
+                .iter()
+                .find(|o| o.opponent == m.opponent || o.response == m.response)
+            {
+                bail!("{} and {} share a symbol", other.name, m.name);
             }
-            Move::Paper => {
-                if other_move == Move::Rock {
-                    WIN_SCORE
-                } else {
-                    LOSE_SCORE
-                }
+        }
+        let mut relation = vec![vec![false; moves.len()]; moves.len()];
+        for (winner, loser) in beats {
+            let (Some(w), Some(l)) = (moves.get(*winner), moves.get(*loser)) else {
+                bail!("unknown move in {winner} beats {loser}");
+            };
+            if winner == loser || relation[*loser][*winner] {
+                bail!("{} and {} cannot beat each other", w.name, l.name);
             }
-            Move::Scissors => {
-                if other_move == Move::Paper {
-                    WIN_SCORE
-                } else {
-                    LOSE_SCORE
-                }
+            relation[*winner][*loser] = true;
+        }
+        for (i, m) in moves.iter().enumerate() {
+            if !relation[i].contains(&true) {
+                bail!("{} beats no move", m.name);
+            }
+            if !relation.iter().any(|b| b[i]) {
+                bail!("{} is beaten by no move", m.name);
             }
         }
+        Ok(Self {
+            moves,
+            beats: relation,
+            lose_score: LOSE_SCORE,
+            draw_score: DRAW_SCORE,
+            win_score: WIN_SCORE,
+        })
+    }
+
+    /// an odd number of moves, each one beating the moves an odd number of places before it
+    /// (cyclically), so that every move beats half of the others
+    pub fn cyclic(moves: Vec<MoveRule>) -> eyre::Result<Self> {
+        let len = moves.len();
+        if len.is_multiple_of(2) {
+            bail!("a cyclic game needs an odd number of moves, not {len}");
+        }
+        let beats: Vec<(usize, usize)> = (0..len)
+            .flat_map(|w| {
+                (1..len)
+                    .step_by(2)
+                    .map(move |offset| (w, (w + len - offset) % len))
+            })
+            .collect();
+        Self::new(moves, &beats)
+    }
+
+    pub fn with_outcome_scores(mut self, lose: usize, draw: usize, win: usize) -> Self {
+        self.lose_score = lose;
+        self.draw_score = draw;
+        self.win_score = win;
+        self
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Self::cyclic(vec![
+            MoveRule::new("Rock", 'A', 'X', 1),
+            MoveRule::new("Paper", 'B', 'Y', 2),
+            MoveRule::new("Scissors", 'C', 'Z', 3),
+        ])
+        .expect("Rock Paper Scissors MUST be a valid game")
+    }
+
+    /// Rock Paper Scissors' moves keep their symbols, Spock and Lizard are D/V and E/W
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Self::cyclic(vec![
+            MoveRule::new("Rock", 'A', 'X', 1),
+            MoveRule::new("Paper", 'B', 'Y', 2),
+            MoveRule::new("Scissors", 'C', 'Z', 3),
+            MoveRule::new("Spock", 'D', 'V', 4),
+            MoveRule::new("Lizard", 'E', 'W', 5),
+        ])
+        .expect("Rock Paper Scissors Lizard Spock MUST be a valid game")
+    }
+
+    pub fn moves(&self) -> impl Iterator<Item = Move> {
+        (0..self.moves.len()).map(Move)
+    }
+
+    pub fn rule(&self, mv: Move) -> &MoveRule {
+        &self.moves[mv.0]
+    }
+
+    pub fn move_score(&self, mv: Move) -> usize {
+        self.moves[mv.0].score
+    }
+
+    /// the outcome of a round, for you
+    pub fn outcome(&self, other_move: Move, your_move: Move) -> Strategy {
+        if self.beats[your_move.0][other_move.0] {
+            Strategy::Win
+        } else if self.beats[other_move.0][your_move.0] {
+            Strategy::Loose
+        } else {
+            Strategy::Draw
+        }
+    }
+
+    pub fn outcome_score(&self, outcome: Strategy) -> usize {
+        match outcome {
+            Strategy::Win => self.win_score,
+            Strategy::Draw => self.draw_score,
+            Strategy::Loose => self.lose_score,
+        }
+    }
+
+    pub fn round_score(&self, other_move: Move, your_move: Move) -> usize {
+        self.outcome_score(self.outcome(other_move, your_move)) + self.move_score(your_move)
+    }
+
+    /// the best scoring move giving the `s` outcome
+    pub fn get_move(&self, other_move: Move, s: Strategy) -> Move {
+        self.moves()
+            .filter(|m| self.outcome(other_move, *m) == s)
+            .max_by_key(|m| (self.move_score(*m), std::cmp::Reverse(m.0)))
+            .expect("validated rules MUST allow any outcome")
+    }
+
+    fn read_symbol(&self, s: &str, symbol: impl Fn(&MoveRule) -> char) -> eyre::Result<Move> {
+        let mut chars = s.trim().chars();
+        let c = match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => bail!("cannot convert '{s}' to Move"),
+        };
+        self.moves()
+            .find(|m| symbol(self.rule(*m)) == c)
+            .ok_or_else(|| eyre!("'{c}' is not a move"))
+    }
+
+    pub fn read_opponent_move(&self, s: &str) -> eyre::Result<Move> {
+        self.read_symbol(s, |m| m.opponent)
+    }
+
+    pub fn read_response(&self, s: &str) -> eyre::Result<Move> {
+        self.read_symbol(s, |m| m.response)
+    }
+
+    pub fn read_round_score(&self, round: &str) -> eyre::Result<usize> {
+        let mut moves = round.split(' ');
+        let other_move =
+            self.read_opponent_move(moves.next().context("no data for other move")?)?;
+        let your_move = self.read_response(moves.next().context("no data for your move")?)?;
+        Ok(self.round_score(other_move, your_move))
+    }
+
+    pub fn read_round_score_with_strategy(&self, round: &str) -> eyre::Result<usize> {
+        let mut datas = round.split(' ');
+        let other_move =
+            self.read_opponent_move(datas.next().context("no data for other move")?)?;
+        let strategy: Strategy = datas.next().context("no data for strategy")?.parse()?;
+        Ok(self.round_score(other_move, self.get_move(other_move, strategy)))
+    }
+}
+
+static ROCK_PAPER_SCISSORS: LazyLock<Rules> = LazyLock::new(Rules::rock_paper_scissors);
+
+pub fn move_score(mv: Move) -> usize {
+    ROCK_PAPER_SCISSORS.move_score(mv)
+}
+
+pub fn round_score(other_move: Move, your_move: Move) -> usize {
+    ROCK_PAPER_SCISSORS.round_score(other_move, your_move)
 }
 
+/// Rock Paper Scissors move, from the symbol of either column
 impl FromStr for Move {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        s.chars()
-            .next()
-            .and_then(|c| match c {
-                'A' => Some(Self::Rock),
-                'X' => Some(Self::Rock),
-                'B' => Some(Self::Paper),
-                'Y' => Some(Self::Paper),
-                'C' => Some(Self::Scissors),
-                'Z' => Some(Self::Scissors),
-                _ => None,
-            })
-            .ok_or(eyre!("cannot convert {s} to Move"))
+        ROCK_PAPER_SCISSORS
+            .read_opponent_move(s)
+            .or_else(|_| ROCK_PAPER_SCISSORS.read_response(s))
     }
 }
 
-fn read_round_score(round: &str) -> usize {
-    let mut moves = round.split(' ');
-    let other_move: Move = moves
-        .next()
-        .and_then(|m| m.parse().ok())
-        .expect("could not convert other move from {move}");
-    let your_move: Move = moves
-        .next()
-        .and_then(|m| m.parse().ok())
-        .expect("could not convert your move from {move}");
-    round_score(other_move, your_move)
+pub fn read_round_score(round: &str) -> eyre::Result<usize> {
+    ROCK_PAPER_SCISSORS.read_round_score(round)
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Strategy {
+/// the expected outcome of a round
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Strategy {
     Win,
     Draw,
     Loose,
@@ -101,63 +264,205 @@ impl FromStr for Strategy {
                 'Z' => Some(Self::Win),
                 _ => None,
             })
-            .context("could not convert {s} to Strategy")
+            .with_context(|| format!("could not convert {s} to Strategy"))
     }
 }
 
-fn get_move(other_move: Move, s: Strategy) -> Move {
-    match s {
-        Strategy::Draw => other_move,
-        Strategy::Loose => match other_move {
-            Move::Rock => Move::Scissors,
-            Move::Paper => Move::Rock,
-            Move::Scissors => Move::Paper,
-        },
-        Strategy::Win => match other_move {
-            Move::Rock => Move::Paper,
-            Move::Paper => Move::Scissors,
-            Move::Scissors => Move::Rock,
-        },
-    }
+pub fn get_move(other_move: Move, s: Strategy) -> Move {
+    ROCK_PAPER_SCISSORS.get_move(other_move, s)
 }
 
-fn read_round_score_with_strategy(round: &str) -> usize {
-    let mut datas = round.split(' ');
-    let other_move: Move = datas
-        .next()
-        .ok_or(eyre!("no data for other move"))
-        .and_then(|d| d.parse::<Move>())
-        .unwrap();
-    let strategy: Strategy = datas
-        .next()
-        .ok_or(eyre!("no data for strategy"))
-        .and_then(|d| d.parse::<Strategy>())
-        .unwrap();
+pub fn read_round_score_with_strategy(round: &str) -> eyre::Result<usize> {
+    ROCK_PAPER_SCISSORS.read_round_score_with_strategy(round)
+}
+
+pub fn compute_full_score(
+    rounds: &str,
+    round_compute: impl Fn(&str) -> eyre::Result<usize>,
+) -> eyre::Result<usize> {
+    Ok(parse_lines(rounds, round_compute)?.into_iter().sum())
+}
 
-    round_score(other_move, get_move(other_move, strategy))
+/// opponent moves of the guide, the second column being ignored
+pub fn read_opponent_moves(rules: &Rules, rounds: &str) -> eyre::Result<Vec<Move>> {
+    parse_lines(rounds, |round| {
+        rules.read_opponent_move(round.split(' ').next().unwrap_or_default())
+    })
 }
 
-fn compute_full_score(rounds: &str, round_compute: impl Fn(&str) -> usize) -> usize {
-    rounds
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(round_compute)
-        .sum()
+/// limits on the responses, so that nobody notices you are cheating
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Constraints {
+    /// number of rounds that must be lost at least
+    pub min_losses: usize,
+    /// the same move cannot be played more times in a row, if any
+    pub max_repeats: Option<usize>,
+}
+
+/// responses to a guide, and the total score they give
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub score: usize,
+    pub responses: Vec<Move>,
+}
+
+/// responses maximising the total score against `opponent_moves`, under `constraints`
+///
+/// dynamic programming on (losses so far, last move, its repetitions), in
+/// O(rounds * min_losses * moves² * max_repeats)
+pub fn best_responses(
+    rules: &Rules,
+    opponent_moves: &[Move],
+    constraints: Constraints,
+) -> eyre::Result<Plan> {
+    if opponent_moves.len() < constraints.min_losses {
+        bail!(
+            "cannot lose {} rounds out of {}",
+            constraints.min_losses,
+            opponent_moves.len()
+        );
+    }
+    let moves: Vec<Move> = rules.moves().collect();
+    // without any limit, repetitions are not tracked
+    let repeats = constraints.max_repeats.unwrap_or(1);
+    let losses = constraints.min_losses + 1;
+    let state =
+        |lost: usize, mv: usize, repeat: usize| (lost * moves.len() + mv) * repeats + repeat;
+    let decode = |s: usize| {
+        (
+            s / repeats / moves.len(),
+            s / repeats % moves.len(),
+            s % repeats,
+        )
+    };
+    const START: usize = usize::MAX;
+
+    // best score reaching each state after a round, and the state it comes from
+    let mut scores: Vec<Option<usize>> = vec![None; losses * moves.len() * repeats];
+    let mut parents: Vec<Vec<usize>> = Vec::with_capacity(opponent_moves.len());
+    for (round, other_move) in opponent_moves.iter().enumerate() {
+        let reached: Vec<(usize, usize)> = match round {
+            0 => vec![(START, 0)],
+            _ => scores
+                .iter()
+                .enumerate()
+                .filter_map(|(s, score)| score.map(|score| (s, score)))
+                .collect(),
+        };
+        let mut next_scores = vec![None; scores.len()];
+        let mut next_parents = vec![START; scores.len()];
+        for (from, score) in reached {
+            let (lost, last, repeat) = match from {
+                START => (0, START, 0),
+                _ => decode(from),
+            };
+            for (i, response) in moves.iter().enumerate() {
+                let repeat = match constraints.max_repeats {
+                    Some(_) if i == last => repeat + 1,
+                    _ => 0,
+                };
+                if repeat >= repeats {
+                    continue;
+                }
+                let outcome = rules.outcome(*other_move, *response);
+                let lost = (lost + usize::from(outcome == Strategy::Loose)).min(losses - 1);
+                let next = state(lost, i, repeat);
+                let score = score + rules.round_score(*other_move, *response);
+                if next_scores[next].is_none_or(|s| s < score) {
+                    next_scores[next] = Some(score);
+                    next_parents[next] = from;
+                }
+            }
+        }
+        scores = next_scores;
+        parents.push(next_parents);
+    }
+
+    let Some((mut current, score)) = scores
+        .iter()
+        .enumerate()
+        .filter(|(s, _)| decode(*s).0 == losses - 1)
+        .filter_map(|(s, score)| score.map(|score| (s, score)))
+        .max_by_key(|(_, score)| *score)
+    else {
+        if opponent_moves.is_empty() {
+            return Ok(Plan {
+                score: 0,
+                responses: vec![],
+            });
+        }
+        bail!("no responses meet the constraints");
+    };
+    let mut responses = Vec::with_capacity(opponent_moves.len());
+    for round_parents in parents.iter().rev() {
+        responses.push(moves[decode(current).1]);
+        current = round_parents[current];
+    }
+    responses.reverse();
+    Ok(Plan { score, responses })
+}
+
+/// a round of the guide, its second column being read both as your response and as the outcome
+struct Round {
+    other_move: Move,
+    response: Move,
+    outcome: Strategy,
+}
+
+impl FromStr for Round {
+    type Err = eyre::Error;
+
+    fn from_str(round: &str) -> Result<Self, Self::Err> {
+        let mut columns = round.split(' ');
+        let other_move = ROCK_PAPER_SCISSORS
+            .read_opponent_move(columns.next().context("no data for other move")?)?;
+        let second = columns.next().context("no data for your move")?;
+        Ok(Self {
+            other_move,
+            response: ROCK_PAPER_SCISSORS.read_response(second)?,
+            outcome: second.parse()?,
+        })
+    }
 }
 
-pub fn play_rock_paper_scissors() {
-    let rounds = include_str!("../resources/day2_rock_paper_scissors.txt");
-    let score = compute_full_score(rounds, read_round_score);
-    let score_with_strategy = compute_full_score(rounds, read_round_score_with_strategy);
+pub struct StrategyGuide {
+    rounds: Vec<Round>,
+}
 
-    println!("full  rock/paper/scissors score : {score}");
-    println!("full  rock/paper/scissors score with strateggy : {score_with_strategy}");
+impl Solution<'_> for StrategyGuide {
+    fn parse(rounds: &str) -> eyre::Result<Self> {
+        Ok(Self {
+            rounds: parse_lines(rounds, str::parse)?,
+        })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        let score: usize = self
+            .rounds
+            .iter()
+            .map(|r| round_score(r.other_move, r.response))
+            .sum();
+        Ok(score.into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        let score: usize = self
+            .rounds
+            .iter()
+            .map(|r| round_score(r.other_move, get_move(r.other_move, r.outcome)))
+            .sum();
+        Ok(score.into())
+    }
+    fn check(rounds: &str) -> Vec<eyre::Report> {
+        check_lines(rounds, str::parse::<Round>)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use itertools::Itertools;
+    // the prelude would shadow Strategy
+    use proptest::{collection, prop_assert, prop_assert_eq, proptest};
 
     #[test]
     fn aoc_example_works() {
@@ -166,10 +471,121 @@ mod tests {
             B X
             C Z
         "};
-        assert_eq!(15, compute_full_score(rounds, read_round_score));
+        assert_eq!(15, compute_full_score(rounds, read_round_score).unwrap());
         assert_eq!(
             12,
-            compute_full_score(rounds, read_round_score_with_strategy)
+            compute_full_score(rounds, read_round_score_with_strategy).unwrap()
         );
     }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        assert!(compute_full_score("A Y\nB W\n", read_round_score).is_err());
+    }
+
+    #[test]
+    fn best_responses_work() {
+        let rules = Rules::rock_paper_scissors();
+        let guide = read_opponent_moves(&rules, "A Y\nB X\nC Z\n").unwrap();
+        let plan = best_responses(&rules, &guide, Constraints::default()).unwrap();
+        assert_eq!(
+            Plan {
+                score: 24,
+                responses: vec![Move::PAPER, Move::SCISSORS, Move::ROCK]
+            },
+            plan
+        );
+
+        let inconspicuous = Constraints {
+            min_losses: 1,
+            max_repeats: None,
+        };
+        let plan = best_responses(&rules, &guide, inconspicuous).unwrap();
+        assert_eq!(19, plan.score);
+        let score = |plan: &Plan| -> usize {
+            guide
+                .iter()
+                .zip(&plan.responses)
+                .map(|(o, y)| rules.round_score(*o, *y))
+                .sum()
+        };
+        assert_eq!(19, score(&plan));
+
+        let rocks = vec![Move::ROCK; 3];
+        let no_triple = Constraints {
+            min_losses: 0,
+            max_repeats: Some(2),
+        };
+        let plan = best_responses(&rules, &rocks, no_triple).unwrap();
+        assert_eq!(8 + 8 + 4, plan.score);
+        assert!(!plan.responses.iter().all_equal());
+
+        let always_losing = Constraints {
+            min_losses: 2,
+            max_repeats: Some(1),
+        };
+        assert!(best_responses(&rules, &rocks[..2], always_losing).is_err());
+        assert!(best_responses(&rules, &[], inconspicuous).is_err());
+    }
+
+    #[test]
+    fn any_cyclic_game_works() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        assert_eq!(Strategy::Win, rules.outcome(Move::SCISSORS, Move::SPOCK));
+        assert_eq!(Strategy::Win, rules.outcome(Move::PAPER, Move::LIZARD));
+        assert_eq!(Strategy::Loose, rules.outcome(Move::ROCK, Move::LIZARD));
+        for m in rules.moves() {
+            let wins = rules
+                .moves()
+                .filter(|o| rules.outcome(*o, m) == Strategy::Win);
+            assert_eq!(2, wins.count());
+        }
+        // Spock beats Rock and Scissors, Spock scores more
+        assert_eq!(Move::SPOCK, rules.get_move(Move::ROCK, Strategy::Win));
+        assert_eq!(4 + 6, rules.read_round_score("C V").unwrap());
+        assert_eq!(3, rules.read_round_score_with_strategy("D X").unwrap());
+
+        let rules = Rules::rock_paper_scissors().with_outcome_scores(1, 2, 10);
+        assert_eq!(2 + 10, rules.read_round_score("A Y").unwrap());
+
+        let moves = vec![
+            MoveRule::new("Left", 'A', 'X', 1),
+            MoveRule::new("Right", 'B', 'Y', 1),
+        ];
+        assert!(Rules::cyclic(moves.clone()).is_err());
+        assert!(Rules::new(moves.clone(), &[(0, 1)]).is_err());
+        assert!(Rules::new(moves, &[(0, 1), (1, 0)]).is_err());
+    }
+
+    #[test]
+    fn strategy_guide_is_parsed_once() {
+        let guide = StrategyGuide::parse("A Y\nB X\nC Z\n").unwrap();
+        assert_eq!(Answer::from(15usize), guide.part1().unwrap());
+        assert_eq!(Answer::from(12usize), guide.part2().unwrap());
+        assert!(StrategyGuide::parse("A Y\nB W\n").is_err());
+        assert!(StrategyGuide::parse("A\n").is_err());
+        assert_eq!(1, StrategyGuide::check("A Y\nD X\nC Z\n").len());
+    }
+
+    proptest! {
+        #[test]
+        fn guide_scores_match_the_rules(rounds in collection::vec((0..3usize, 0..3usize), 1..50)) {
+            let guide = rounds
+                .iter()
+                .map(|(a, b)| format!("{} {}\n", ["A", "B", "C"][*a], ["X", "Y", "Z"][*b]))
+                .collect::<String>();
+            // the response beats the opponent when it is the next move, cyclically
+            let outcome = |a: usize, b: usize| (b + 4 - a) % 3;
+            let responses = rounds.iter().map(|(a, b)| b + 1 + 3 * outcome(*a, *b)).sum::<usize>();
+            let strategies = rounds.iter().map(|(a, s)| (a + s + 2) % 3 + 1 + 3 * s).sum::<usize>();
+            prop_assert_eq!(responses, compute_full_score(&guide, read_round_score).unwrap());
+            prop_assert_eq!(strategies, compute_full_score(&guide, read_round_score_with_strategy).unwrap());
+
+            let rules = Rules::rock_paper_scissors();
+            let opponent_moves = read_opponent_moves(&rules, &guide).unwrap();
+            let plan = best_responses(&rules, &opponent_moves, Constraints::default()).unwrap();
+            prop_assert_eq!(rounds.len(), plan.responses.len());
+            prop_assert!(plan.score >= responses.max(strategies));
+        }
+    }
 }
@@ -1,3 +1,7 @@
+use crate::parsing::{check_lines, or_parse_error, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::bail;
+
 fn to_index(current_index: usize, offset: isize, len: usize) -> usize {
     assert!(
         (0..len).contains(&current_index),
@@ -28,12 +32,12 @@ fn to_index(current_index: usize, offset: isize, len: usize) -> usize {
 }
 
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
-struct Coordinate {
+pub struct Coordinate {
     value: isize,
     rank: usize,
 }
 
-fn reorder_numbers(numbers: &mut [Coordinate], factor: usize, times: usize) {
+pub fn reorder_numbers(numbers: &mut [Coordinate], factor: usize, times: usize) {
     let len = numbers.len();
 
     let mut current_index = 0;
@@ -44,7 +48,6 @@ fn reorder_numbers(numbers: &mut [Coordinate], factor: usize, times: usize) {
     for _ in 0..times {
         for i in 0..len {
             if i != numbers[current_index].rank {
-                // print!("*");
                 current_index = numbers
                     .iter()
                     .enumerate()
@@ -60,7 +63,6 @@ fn reorder_numbers(numbers: &mut [Coordinate], factor: usize, times: usize) {
                 continue;
             }
             let new_index = to_index(current_index, number.value, len);
-            // println!("\nmoving {number} ({current_index} => {new_index})");
 
             if new_index < current_index {
                 for i in 0..(current_index - new_index) {
@@ -69,9 +71,6 @@ fn reorder_numbers(numbers: &mut [Coordinate], factor: usize, times: usize) {
                 current_index = (current_index + 1) % len;
             } else {
                 for i in 0..(new_index - current_index) {
-                    if current_index + i + 1 == len {
-                        println!("{current_index} => {new_index} ({i})");
-                    }
                     numbers[current_index + i] = numbers[current_index + i + 1];
                 }
             }
@@ -80,7 +79,7 @@ fn reorder_numbers(numbers: &mut [Coordinate], factor: usize, times: usize) {
     }
 }
 
-fn summ_offsets(numbers: &[Coordinate]) -> isize {
+pub fn summ_offsets(numbers: &[Coordinate]) -> isize {
     let start_index = numbers
         .iter()
         .enumerate()
@@ -94,28 +93,45 @@ fn summ_offsets(numbers: &[Coordinate]) -> isize {
         .sum()
 }
 
-pub fn reach_elves() {
-    let encrypted_coordinates: Vec<Coordinate> =
-        include_str!("../resources/day20_encrypted_coordinates.txt")
-            .lines()
+pub struct EncryptedFile {
+    coordinates: Vec<Coordinate>,
+}
+
+impl EncryptedFile {
+    pub fn decrypt(&self, key: usize, rounds: usize) -> isize {
+        let mut coordinates = self.coordinates.clone();
+        reorder_numbers(&mut coordinates, key, rounds);
+        summ_offsets(&coordinates)
+    }
+}
+
+impl Solution<'_> for EncryptedFile {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        let values: Vec<isize> = parse_lines(input, |l| Ok(l.trim().parse()?))?;
+        if values.len() < 2 {
+            bail!("cannot mix less than 2 numbers");
+        }
+        if values.iter().filter(|v| **v == 0).count() != 1 {
+            bail!("expecting a single 0 among the numbers");
+        }
+        let coordinates = values
+            .into_iter()
             .enumerate()
-            .filter_map(|(rank, l)| {
-                l.parse::<isize>()
-                    .map(|value| Coordinate { value, rank })
-                    .ok()
-            })
+            .map(|(rank, value)| Coordinate { value, rank })
             .collect();
-    {
-        let mut encrypted_coordinates = encrypted_coordinates.clone();
-        reorder_numbers(&mut encrypted_coordinates, 1, 1);
-        let coordinates_sum = summ_offsets(&encrypted_coordinates);
-        println!("sum of coordinates : {coordinates_sum}");
+        Ok(Self { coordinates })
     }
-    {
-        let mut encrypted_coordinates = encrypted_coordinates;
-        reorder_numbers(&mut encrypted_coordinates, 811589153, 10);
-        let coordinates_sum = summ_offsets(&encrypted_coordinates);
-        println!("actual sum of coordinates : {coordinates_sum}");
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(self.decrypt(1, 1).into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(self.decrypt(811589153, 10).into())
+    }
+    fn check(input: &str) -> Vec<eyre::Report> {
+        or_parse_error(
+            check_lines(input, |l| Ok(l.trim().parse::<isize>()?)),
+            || Self::parse(input),
+        )
     }
 }
 
@@ -123,10 +139,12 @@ pub fn reach_elves() {
 mod tests {
     use super::*;
     use itertools::Itertools;
+    use proptest::prelude::*;
 
     #[test]
     fn aoc_example_works() {
-        assert!((-1isize % 16).is_negative());
+        let minus_one = -1isize;
+        assert!((minus_one % 16).is_negative());
         let numbers: Vec<Coordinate> = [1, 2, -3, 3, -2, 0, 4]
             .into_iter()
             .enumerate()
@@ -149,4 +167,54 @@ mod tests {
         reorder_numbers(&mut numbers, 811589153, 10);
         assert_eq!(1623178306, summ_offsets(&numbers));
     }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = EncryptedFile::parse("1\n2\n-\n0\n").err().unwrap();
+        assert_eq!("line 3 : '-'", error.to_string());
+        assert!(EncryptedFile::parse("1\n2\n3\n").is_err());
+    }
+
+    /// numbers with a single 0
+    fn numbers() -> impl Strategy<Value = Vec<isize>> {
+        (
+            prop::collection::vec(-30..30isize, 1..30),
+            any::<prop::sample::Index>(),
+        )
+            .prop_map(|(numbers, zero)| {
+                let mut numbers = numbers
+                    .into_iter()
+                    .map(|n| if n == 0 { 30 } else { n })
+                    .collect_vec();
+                numbers.insert(zero.index(numbers.len() + 1), 0);
+                numbers
+            })
+    }
+
+    /// grove coordinates, moving each number by removing it from the circle then inserting it back
+    fn mix(numbers: &[isize], key: isize, rounds: usize) -> isize {
+        let mut circle = numbers.iter().map(|n| n * key).enumerate().collect_vec();
+        let len = circle.len() as isize;
+        for _ in 0..rounds {
+            for rank in 0..circle.len() {
+                let i = circle.iter().position(|(r, _)| *r == rank).unwrap();
+                let number = circle.remove(i);
+                circle.insert((i as isize + number.1).rem_euclid(len - 1) as usize, number);
+            }
+        }
+        let zero = circle.iter().position(|(_, n)| *n == 0).unwrap();
+        [1000, 2000, 3000]
+            .into_iter()
+            .map(|offset| circle[(zero + offset) % circle.len()].1)
+            .sum()
+    }
+
+    proptest! {
+        #[test]
+        fn numbers_can_be_mixed(numbers in numbers()) {
+            let file = EncryptedFile::parse(&numbers.iter().join("\n")).unwrap();
+            prop_assert_eq!(Answer::from(mix(&numbers, 1, 1)), file.part1().unwrap());
+            prop_assert_eq!(Answer::from(mix(&numbers, 811589153, 10)), file.part2().unwrap());
+        }
+    }
 }
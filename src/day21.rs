@@ -1,10 +1,11 @@
 use crate::day21::Source::{Ref, Val};
-use eyre::{eyre, ContextCompat};
+use crate::parsing::{check_lines, or_parse_error, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, ContextCompat};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::iter::once;
-use std::ops::{Add, Div, Mul, Sub};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 enum Source<'s> {
@@ -59,7 +60,7 @@ impl<'s> Display for Shout<'s> {
 }
 
 #[derive(Debug, Copy, Clone)]
-struct Shouter<'s> {
+pub struct Shouter<'s> {
     name: &'s str,
     shout: Shout<'s>,
 }
@@ -73,12 +74,13 @@ impl<'s> Display for Shouter<'s> {
 }
 
 impl<'s> Shouter<'s> {
-    fn try_new(s: &'s str) -> eyre::Result<Self> {
+    pub fn try_new(s: &'s str) -> eyre::Result<Self> {
         let mut words = s.split_whitespace();
         let name = words
             .next()
-            .map(|name| &name[..name.len() - 1])
-            .context("reading name")?;
+            .context("reading name")?
+            .strip_suffix(':')
+            .context("no ':' after name")?;
 
         let source_a: Source = words
             .next()
@@ -93,6 +95,9 @@ impl<'s> Shouter<'s> {
                 let source_b: Source = words
                     .next()
                     .map_or_else(|| Err(eyre!("no second source")), Source::try_new)?;
+                if let Some(extra) = words.next() {
+                    bail!("unexpected '{extra}' after second source");
+                }
                 match op {
                     "+" => Ok(Self {
                         name,
@@ -157,11 +162,11 @@ impl<'s> Shouter<'s> {
             }),
             _ => None,
         };
-        // println!("{self} promote {source} => {result:?}");
         result
     }
 
-    fn reference_known_vals(&self, known_vals_by_name: &HashMap<&'s str, isize>) -> bool {
+    /// what the shout is made of
+    fn sources(&self) -> Vec<Source<'s>> {
         match self.shout {
             Shout::Add((a, b)) | Shout::Sub((a, b)) | Shout::Mul((a, b)) | Shout::Div((a, b)) => {
                 vec![a, b]
@@ -170,14 +175,19 @@ impl<'s> Shouter<'s> {
                 vec![a]
             }
         }
-        .into_iter()
-        .any(|s| {
+    }
+
+    fn reference_known_vals(&self, known_vals_by_name: &HashMap<&'s str, isize>) -> bool {
+        self.sources().into_iter().any(|s| {
             s.get_name()
                 .map(|name| known_vals_by_name.contains_key(name))
                 .unwrap_or(false)
         })
     }
-    fn replace_known_vals(&self, known_vals_by_name: &mut HashMap<&'s str, isize>) -> Self {
+    fn replace_known_vals(
+        &self,
+        known_vals_by_name: &mut HashMap<&'s str, isize>,
+    ) -> eyre::Result<Self> {
         fn replace_source<'s>(
             source: Source<'s>,
             known_vals_by_name: &HashMap<&'s str, isize>,
@@ -200,12 +210,12 @@ impl<'s> Shouter<'s> {
             )
         }
 
-        if let Some((name, val)) = resolve(self, known_vals_by_name) {
+        if let Some((name, val)) = resolve(self, known_vals_by_name)? {
             known_vals_by_name.insert(name, val);
-            return Self {
+            return Ok(Self {
                 name,
                 shout: Shout::Val(Val(val)),
-            };
+            });
         }
 
         let shout = match self.shout {
@@ -228,7 +238,7 @@ impl<'s> Shouter<'s> {
         if let Shout::Val(Val(val)) = result.shout {
             known_vals_by_name.insert(result.name, val);
         }
-        result
+        Ok(result)
     }
 }
 
@@ -290,21 +300,23 @@ impl<'s> Shouting<'s> {
                     .filter_map(|source| {
                         if !sources.contains(source.name) {
                             sources.insert(source.name);
-                            match source.shout {
+                            let referenced = match source.shout {
                                 Shout::Add((a, b))
                                 | Shout::Sub((a, b))
                                 | Shout::Mul((a, b))
-                                | Shout::Div((a, b)) => Some(
-                                    [a, b]
-                                        .iter()
-                                        .filter_map(|f| {
-                                            f.get_name()
-                                                .and_then(|name| self.shouters_by_name.get(name))
-                                        })
-                                        .collect_vec(),
-                                ),
-                                Shout::Val(_) => None,
-                            }
+                                | Shout::Div((a, b)) => vec![a, b],
+                                // an equality refers to its other member
+                                Shout::Val(a) => vec![a],
+                            };
+                            Some(
+                                referenced
+                                    .iter()
+                                    .filter_map(|f| {
+                                        f.get_name()
+                                            .and_then(|name| self.shouters_by_name.get(name))
+                                    })
+                                    .collect_vec(),
+                            )
                         } else {
                             None
                         }
@@ -324,18 +336,28 @@ fn deref(source: Source, vals: &HashMap<&str, isize>) -> Option<isize> {
     }
 }
 
-fn resolve<'s>(shouter: &Shouter<'s>, vals: &HashMap<&str, isize>) -> Option<(&'s str, isize)> {
+fn resolve<'s>(
+    shouter: &Shouter<'s>,
+    vals: &HashMap<&str, isize>,
+) -> eyre::Result<Option<(&'s str, isize)>> {
     let name = shouter.name;
 
-    let solve = |a, b, f: fn(isize, isize) -> isize| {
-        deref(a, vals).and_then(|a| deref(b, vals).map(|b| (name, f(a, b))))
+    let solve = |a, b, f: fn(isize, isize) -> Option<isize>| {
+        let (Some(a), Some(b)) = (deref(a, vals), deref(b, vals)) else {
+            return Ok(None);
+        };
+        match f(a, b) {
+            Some(v) => Ok(Some((name, v))),
+            None if b == 0 => Err(eyre!("{shouter} : division by 0")),
+            None => Err(eyre!("{shouter} : overflow")),
+        }
     };
     match shouter.shout {
-        Shout::Val(s) => deref(s, vals).map(|v| (name, v)),
-        Shout::Add((a, b)) => solve(a, b, isize::add),
-        Shout::Sub((a, b)) => solve(a, b, isize::sub),
-        Shout::Mul((a, b)) => solve(a, b, isize::mul),
-        Shout::Div((a, b)) => solve(a, b, isize::div),
+        Shout::Val(s) => Ok(deref(s, vals).map(|v| (name, v))),
+        Shout::Add((a, b)) => solve(a, b, isize::checked_add),
+        Shout::Sub((a, b)) => solve(a, b, isize::checked_sub),
+        Shout::Mul((a, b)) => solve(a, b, isize::checked_mul),
+        Shout::Div((a, b)) => solve(a, b, isize::checked_div),
     }
 }
 
@@ -343,19 +365,16 @@ fn reduce_shouts<'s>(
     shouting: &Shouting<'s>,
     vals: &mut HashMap<&'s str, isize>,
     source: &str,
-) -> bool {
+) -> eyre::Result<bool> {
     let mut sources = shouting.get_sources(source);
     let mut reduced = false;
     loop {
         let to_remove: Vec<_> = sources
             .iter()
-            .filter_map(|source| {
-                shouting
-                    .shouters_by_name
-                    .get(source)
-                    .and_then(|shouter| resolve(shouter, vals))
-            })
-            .collect();
+            .filter_map(|source| shouting.shouters_by_name.get(source))
+            .map(|shouter| resolve(shouter, vals))
+            .flatten_ok()
+            .collect::<eyre::Result<_>>()?;
         if to_remove.is_empty() {
             break;
         }
@@ -366,17 +385,19 @@ fn reduce_shouts<'s>(
             sources.remove(&name);
         }
     }
-    reduced
+    Ok(reduced)
 }
 
-fn root_shout(shouters: &[Shouter]) -> isize {
+pub fn root_shout(shouters: &[Shouter]) -> eyre::Result<isize> {
     let shouting = Shouting::new(shouters);
     let mut vals: HashMap<&str, isize> = HashMap::with_capacity(shouters.len());
 
-    reduce_shouts(&shouting, &mut vals, "root");
-    *vals.get("root").unwrap()
+    reduce_shouts(&shouting, &mut vals, "root")?;
+    vals.get("root")
+        .copied()
+        .context("root shout cannot be resolved")
 }
-fn equality_human_shout(shouters: &[Shouter]) -> isize {
+pub fn equality_human_shout(shouters: &[Shouter]) -> eyre::Result<isize> {
     let mut shouting = Shouting::new(shouters);
 
     // removed non existant rule
@@ -385,25 +406,32 @@ fn equality_human_shout(shouters: &[Shouter]) -> isize {
     let mut vals: HashMap<&str, isize> = HashMap::with_capacity(shouters.len());
 
     // ID force equality
-    let root = shouting.shouters_by_name.remove("root").unwrap();
+    let root = shouting
+        .shouters_by_name
+        .remove("root")
+        .context("no root monkey")?;
     let (mut equal_a, mut equal_b) = match root.shout {
         Shout::Add((a, b)) | Shout::Sub((a, b)) | Shout::Mul((a, b)) | Shout::Div((a, b)) => (a, b),
-        _ => panic!("root cannot be immediatly defined, would mean any number is a solution"),
+        _ => bail!("root cannot be immediatly defined, would mean any number is a solution"),
     };
-    if let Val(_v) = equal_a {
+    // the human must be on the replaced side, the other one defining it
+    if let (Val(_), _) | (_, Ref("humn")) = (equal_a, equal_b) {
         std::mem::swap(&mut equal_b, &mut equal_a);
+    } else if let Ref(name_b) = equal_b {
+        if shouting.get_defined_on("humn").contains(&name_b) {
+            std::mem::swap(&mut equal_b, &mut equal_a);
+        }
     }
     let replaced_source = if let Ref(replaced_source) = equal_a {
         replaced_source
     } else {
-        panic!("{root} : should not happen, switched them to avoid this !!");
+        bail!("{root} : both members are numbers, there is nothing to solve");
     };
     if let Val(v) = equal_b {
         vals.insert(replaced_source, v);
     }
 
     // replace all reference to first equality member
-    // println!("---- replacing {replaced_source} with {equal_b}");
     shouting.shouters_by_name = shouting
         .shouters_by_name
         .into_iter()
@@ -462,8 +490,8 @@ fn equality_human_shout(shouters: &[Shouter]) -> isize {
         shouting.shouters_by_name = shouting
             .shouters_by_name
             .into_iter()
-            .map(|(name, shouter)| (name, shouter.replace_known_vals(&mut vals)))
-            .collect();
+            .map(|(name, shouter)| Ok((name, shouter.replace_known_vals(&mut vals)?)))
+            .collect::<eyre::Result<_>>()?;
     }
 
     //  reverse non resolved rules starting from "humn"
@@ -498,11 +526,11 @@ fn equality_human_shout(shouters: &[Shouter]) -> isize {
                 shout: Shout::Val(Ref(name_b)),
             },
         );
-        reduce_shouts(&shouting, &mut vals, replaced_source);
+        reduce_shouts(&shouting, &mut vals, replaced_source)?;
     }
 
     // resolve human
-    reduce_shouts(&shouting, &mut vals, "humn");
+    reduce_shouts(&shouting, &mut vals, "humn")?;
     while shouting
         .shouters_by_name
         .values()
@@ -511,30 +539,89 @@ fn equality_human_shout(shouters: &[Shouter]) -> isize {
         shouting.shouters_by_name = shouting
             .shouters_by_name
             .into_iter()
-            .map(|(name, shouter)| (name, shouter.replace_known_vals(&mut vals)))
-            .collect();
+            .map(|(name, shouter)| Ok((name, shouter.replace_known_vals(&mut vals)?)))
+            .collect::<eyre::Result<_>>()?;
     }
 
-    *vals.get("humn").unwrap()
+    let humn = vals
+        .get("humn")
+        .copied()
+        .context("human shout cannot be resolved")?;
+    // an inverted product may not divide exactly, leaving root unbalanced
+    let (a, b) = root_members(shouters, humn)?;
+    if a != b {
+        bail!("no human shout balances root, {humn} gives {a} and {b}");
+    }
+    Ok(humn)
 }
 
-pub fn solve_riddles() {
-    let input = include_str!("../resources/day21_shouting.txt");
-    let shoutings: Vec<Shouter> = input
-        .lines()
-        .filter_map(|l| Shouter::try_new(l).ok())
-        .collect();
+/// what both members of root shout, once the human shouts `humn`
+fn root_members(shouters: &[Shouter], humn: isize) -> eyre::Result<(isize, isize)> {
+    let mut shouting = Shouting::new(shouters);
+    shouting.shouters_by_name.insert(
+        "humn",
+        Shouter {
+            name: "humn",
+            shout: Shout::Val(Val(humn)),
+        },
+    );
+    let mut vals: HashMap<&str, isize> = HashMap::with_capacity(shouters.len());
+    reduce_shouts(&shouting, &mut vals, "root")?;
+    match shouting.shouters_by_name.get("root").map(|root| root.shout) {
+        Some(Shout::Add((a, b)) | Shout::Sub((a, b)) | Shout::Mul((a, b)) | Shout::Div((a, b))) => {
+            deref(a, &vals)
+                .zip(deref(b, &vals))
+                .context("root members cannot be resolved")
+        }
+        _ => bail!("root has no members"),
+    }
+}
 
-    let root_shout = root_shout(&shoutings);
-    println!("root shouts {root_shout}");
+pub struct Riddle<'s> {
+    shoutings: Vec<Shouter<'s>>,
+}
 
-    let human_shout = equality_human_shout(&shoutings);
-    println!("human equality shout {human_shout}");
+impl<'s> Solution<'s> for Riddle<'s> {
+    fn parse(input: &'s str) -> eyre::Result<Self> {
+        let shoutings = parse_lines(input, Shouter::try_new)?;
+        let mut names = HashSet::with_capacity(shoutings.len());
+        for shouter in &shoutings {
+            if !names.insert(shouter.name) {
+                bail!("monkey {} shouts twice", shouter.name);
+            }
+        }
+        for name in ["root", "humn"] {
+            if !names.contains(name) {
+                bail!("no {name} monkey");
+            }
+        }
+        for shouter in &shoutings {
+            if let Some(unknown) = shouter
+                .sources()
+                .iter()
+                .filter_map(Source::get_name)
+                .find(|name| !names.contains(name))
+            {
+                bail!("monkey {} waits for unknown monkey {unknown}", shouter.name);
+            }
+        }
+        Ok(Self { shoutings })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(root_shout(&self.shoutings)?.into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(equality_human_shout(&self.shoutings)?.into())
+    }
+    fn check(input: &'s str) -> Vec<eyre::Report> {
+        or_parse_error(check_lines(input, Shouter::try_new), || Self::parse(input))
+    }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn aoc_example_works() {
@@ -555,11 +642,147 @@ mod tests {
             drzm: hmdt - zczc
             hmdt: 32
         "};
-        let shoutings: Vec<Shouter> = input
-            .lines()
-            .filter_map(|l| Shouter::try_new(l).ok())
-            .collect();
-        assert_eq!(152, root_shout(&shoutings));
-        assert_eq!(301, equality_human_shout(&shoutings));
+        let shoutings: Vec<Shouter> = parse_lines(input, Shouter::try_new).unwrap();
+        assert_eq!(152, root_shout(&shoutings).unwrap());
+        assert_eq!(301, equality_human_shout(&shoutings).unwrap());
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = Riddle::parse("root: pppw + sjmn\ndbpl 5\n").err().unwrap();
+        assert_eq!("line 2 : 'dbpl 5'", error.to_string());
+        let no_root = parse_lines("dbpl: 5\n", Shouter::try_new).unwrap();
+        assert!(root_shout(&no_root).is_err());
+    }
+
+    #[test]
+    fn human_can_be_on_either_side_of_root() {
+        let input = "root: dbpl + pppw\npppw: humn - dvpt\ndbpl: 5\ndvpt: 3\nhumn: 1";
+        let shoutings: Vec<Shouter> = parse_lines(input, Shouter::try_new).unwrap();
+        assert_eq!(8, equality_human_shout(&shoutings).unwrap());
+        let shoutings: Vec<Shouter> =
+            parse_lines("root: dbpl + humn\ndbpl: 5\nhumn: 1", Shouter::try_new).unwrap();
+        assert_eq!(5, equality_human_shout(&shoutings).unwrap());
+    }
+
+    #[test]
+    fn divisions_are_checked() {
+        let input = "root: aaaa + humn\naaaa: bbbb / cccc\nbbbb: 7\ncccc: 2\nhumn: 1";
+        let shoutings: Vec<Shouter> = parse_lines(input, Shouter::try_new).unwrap();
+        assert_eq!(4, root_shout(&shoutings).unwrap());
+
+        let input = "root: aaaa + humn\naaaa: bbbb / cccc\nbbbb: 7\ncccc: 0\nhumn: 1";
+        let shoutings: Vec<Shouter> = parse_lines(input, Shouter::try_new).unwrap();
+        let error = root_shout(&shoutings).err().unwrap();
+        assert_eq!("aaaa = bbbb / cccc : division by 0", error.to_string());
+
+        let input = "root: aaaa + bbbb\naaaa: humn * cccc\nbbbb: 7\ncccc: 2\nhumn: 1";
+        let shoutings: Vec<Shouter> = parse_lines(input, Shouter::try_new).unwrap();
+        let error = equality_human_shout(&shoutings).err().unwrap();
+        assert_eq!(
+            "no human shout balances root, 3 gives 6 and 7",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn riddle_check_works() {
+        let problems = Riddle::check("dbpl: 5\nabcd: dbpl + zzzz\n");
+        assert_eq!(1, problems.len());
+        assert_eq!("no root monkey", problems[0].to_string());
+
+        let riddle = "root: dbpl + zzzz\ndbpl: 5\nhumn: 1\n";
+        let error = Riddle::parse(riddle).err().unwrap();
+        assert_eq!(
+            "monkey root waits for unknown monkey zzzz",
+            error.to_string()
+        );
+        let error = Riddle::parse("root: dbpl + dbpl\ndbpl: 5\n").err().unwrap();
+        assert_eq!("no humn monkey", error.to_string());
+        let error = Riddle::parse("root: dbpl + humn\ndbpl: 5\nhumn: 1\ndbpl: 2\n")
+            .err()
+            .unwrap();
+        assert_eq!("monkey dbpl shouts twice", error.to_string());
+        assert!(Riddle::check("root: dbpl + humn\ndbpl: 5\nhumn: 1\n").is_empty());
+    }
+
+    /// monkey jobs forming a tree, combining numbers up to the root, one of them being humn
+    fn riddle() -> impl Strategy<Value = String> {
+        prop::collection::vec(1..10isize, 2..10)
+            .prop_flat_map(|numbers| {
+                let operations = prop::collection::vec(
+                    (
+                        any::<prop::sample::Index>(),
+                        any::<prop::sample::Index>(),
+                        prop::sample::select(vec!['+', '-', '*']),
+                    ),
+                    numbers.len() - 1,
+                );
+                (Just(numbers), operations)
+            })
+            .prop_map(|(numbers, operations)| {
+                let mut pending = (0..numbers.len())
+                    .map(|i| match i {
+                        0 => "humn".to_string(),
+                        _ => format!("n{i}"),
+                    })
+                    .collect_vec();
+                let mut jobs = pending
+                    .iter()
+                    .zip(&numbers)
+                    .map(|(name, number)| format!("{name}: {number}"))
+                    .collect_vec();
+                for (i, (a, b, op)) in operations.iter().enumerate() {
+                    let a = pending.swap_remove(a.index(pending.len()));
+                    let b = pending.swap_remove(b.index(pending.len()));
+                    let name = match pending.is_empty() {
+                        true => "root".to_string(),
+                        false => format!("m{i}"),
+                    };
+                    jobs.push(format!("{name}: {a} {op} {b}"));
+                    pending.push(name);
+                }
+                jobs.join("\n")
+            })
+    }
+
+    /// what `name` shouts, humn shouting `humn` if any
+    fn shout(jobs: &HashMap<&str, Vec<&str>>, name: &str, humn: Option<isize>) -> isize {
+        match (name, jobs[name].as_slice()) {
+            ("humn", _) if humn.is_some() => humn.unwrap(),
+            (_, [number]) => number.parse().unwrap(),
+            (_, [a, op, b]) => {
+                let (a, b) = (shout(jobs, a, humn), shout(jobs, b, humn));
+                match *op {
+                    "+" => a + b,
+                    "-" => a - b,
+                    _ => a * b,
+                }
+            }
+            _ => unreachable!("jobs are generated"),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn riddle_can_be_solved(riddle in riddle()) {
+            let jobs: HashMap<&str, Vec<&str>> = riddle
+                .lines()
+                .map(|l| {
+                    let (name, job) = l.split_once(": ").unwrap();
+                    (name, job.split(' ').collect())
+                })
+                .collect();
+            let riddle = Riddle::parse(&riddle).unwrap();
+            prop_assert_eq!(Answer::from(shout(&jobs, "root", None)), riddle.part1().unwrap());
+
+            // a product by 0 may leave any or no humn shout
+            if let Ok(Answer::Int(humn)) = riddle.part2() {
+                let humn = Some(humn as isize);
+                prop_assert_eq!(shout(&jobs, jobs["root"][0], humn), shout(&jobs, jobs["root"][2], humn));
+            } else {
+                prop_assert!(jobs.values().any(|job| job.get(1) == Some(&"*")));
+            }
+        }
     }
 }
@@ -1,7 +1,9 @@
 use crate::day22::Direction::{East, North, South, West};
 use crate::day22::Step::{Forward, Left, Right};
 use crate::day22::Tile::{Open, Wall};
-use eyre::{eyre, ContextCompat, WrapErr};
+use crate::parsing::at_line;
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, ContextCompat, WrapErr};
 use std::collections::HashMap;
 use std::str::FromStr;
 
@@ -79,7 +81,12 @@ impl FromStr for MapRow {
         let offset = s.chars().take_while(|c| *c == ' ').count();
         let tiles: Result<Vec<Tile>, _> = s[offset..]
             .chars()
-            .map(|c| String::from(c).parse())
+            .enumerate()
+            .map(|(i, c)| {
+                String::from(c)
+                    .parse()
+                    .wrap_err_with(|| format!("at column {}", offset + i + 1))
+            })
             .collect();
         let tiles = tiles?;
         Ok(Self { offset, tiles })
@@ -114,7 +121,7 @@ impl FromStr for Step {
     }
 }
 #[derive(Debug, Clone)]
-struct Map {
+pub struct Map {
     rows: Vec<MapRow>,
     path: Vec<Step>,
 }
@@ -126,14 +133,19 @@ impl FromStr for Map {
         let rows: Result<Vec<MapRow>, _> = s
             .lines()
             .take_while(|l| l.starts_with([' ', '.', '#']))
-            .map(str::parse)
+            .enumerate()
+            .map(|(i, l)| l.parse().wrap_err_with(|| at_line(i)))
             .collect();
         let rows = rows?;
+        if rows.is_empty() {
+            bail!("no map before the path");
+        }
         let path_line = s
             .lines()
             .skip(rows.len())
             .find(|l| !l.trim().is_empty())
-            .with_context(|| "reading path")?;
+            .with_context(|| "reading path")?
+            .trim_end();
 
         let path_indices: Vec<_> = path_line.match_indices(&['L', 'R']).collect();
         let mut path: Vec<Step> = vec![];
@@ -288,7 +300,7 @@ impl Map {
         }
     }
 
-    fn password(self) -> usize {
+    pub fn password(self) -> usize {
         let mut pos = Pos {
             column: self.rows[0].offset,
             row: 0,
@@ -325,6 +337,14 @@ struct CubeFace {
     top_left: Pos,
     bottom_right: Pos,
 }
+
+impl CubeFace {
+    fn fits(&self, map: &Map) -> bool {
+        [self.top_left, self.bottom_right]
+            .into_iter()
+            .all(|p| p.row < map.rows.len() && map.get_tile(p).is_some())
+    }
+}
 /// I guess the wrap up could be computed
 ///
 #[derive(Debug)]
@@ -449,30 +469,10 @@ impl<'m> Cube<'m> {
                 panic!("should not happens, we were supposed to cross a seam with ({current_face_idx}, {current_dir:?})" );
             }
         }
-        // dbg!((pos,current_dir))
         (current_pos, current_dir)
     }
 
     fn apply_path(&self) -> usize {
-        let max_fwd = self
-            .map
-            .path
-            .iter()
-            .filter_map(|step| {
-                if let Forward(fwd) = *step {
-                    Some(fwd)
-                } else {
-                    None
-                }
-            })
-            .max()
-            .unwrap();
-        let face_size = self.faces[0].bottom_right.column - self.faces[0].top_left.column + 1;
-        assert!(
-            max_fwd <= face_size,
-            "{max_fwd} > {face_size} :move from more than one face at once must be split"
-        );
-
         let mut pos = self.faces[0].top_left;
         let mut dir = Direction::East;
 
@@ -519,15 +519,16 @@ impl<'m> Cube<'m> {
     }
 }
 
-pub fn decode_password() {
-    let input = include_str!("../resources/day22_map_password.txt");
-    let map: Map = input.parse().unwrap();
-    let password = map.clone().password();
-    println!("password : {password}");
-
-    let cube = Cube::new(
-        &map,
-        [
+impl Solution<'_> for Map {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        input.parse()
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(self.clone().password().into())
+    }
+    /// the cube faces and seams are the ones of the actual input, not the example's
+    fn part2(&self) -> eyre::Result<Answer> {
+        let faces = [
             CubeFace {
                 top_left: Pos {
                     row: 0,
@@ -585,26 +586,34 @@ pub fn decode_password() {
                     column: 49,
                 },
             },
-        ],
-        HashMap::from([
-            ((0, North), (5, North)),
-            ((0, East), (3, West)),
-            ((0, South), (2, West)),
-            ((1, North), (5, East)),
-            ((1, West), (4, East)),
-            ((2, West), (4, South)),
-            ((3, South), (5, West)),
-        ]),
-    );
-
-    let cube_password = cube.apply_path();
-    println!("cube_password : {cube_password}");
+        ];
+        if let Some(face) = faces.iter().position(|f| !f.fits(self)) {
+            bail!("face {face} of the actual input cube layout is not on the map");
+        }
+        let cube = Cube::new(
+            self,
+            faces,
+            HashMap::from([
+                ((0, North), (5, North)),
+                ((0, East), (3, West)),
+                ((0, South), (2, West)),
+                ((1, North), (5, East)),
+                ((1, West), (4, East)),
+                ((2, West), (4, South)),
+                ((3, South), (5, West)),
+            ]),
+        );
+
+        Ok(cube.apply_path().into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use itertools::Itertools;
+    use proptest::prelude::*;
 
     #[test]
     fn aoc_example_works() {
@@ -694,4 +703,102 @@ mod tests {
         dbg!(&cube);
         assert_eq!(5031, cube.apply_path());
     }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let map: Map = "  ..#\n  ...\n\n10R5".parse().unwrap();
+        assert!(
+            map.part2().is_err(),
+            "map is not shaped as the actual input"
+        );
+        assert!("  ..#\n  .x.\n\n10R5".parse::<Map>().is_err());
+    }
+
+    /// offset and walls of a map row
+    type Row = (usize, Vec<bool>);
+
+    /// map rows whose offsets and ends never decrease, so that each column is a single stretch
+    /// the first tile being open, then the path with its steps
+    fn notes() -> impl Strategy<Value = (Vec<Row>, Vec<Step>, String)> {
+        let rows = prop::collection::vec(
+            (
+                0..3usize,
+                0..3usize,
+                prop::collection::vec(prop::bool::weighted(0.25), 12),
+            ),
+            1..6,
+        );
+        let turns = prop::collection::vec((0..6usize, prop::bool::ANY), 0..8);
+        (rows, turns, 0..6usize).prop_map(|(rows, turns, last)| {
+            let (mut offset, mut end) = (0, 0);
+            let mut rows = rows
+                .into_iter()
+                .map(|(shift, stretch, walls)| {
+                    offset += shift;
+                    end = (end + stretch).max(offset + 1);
+                    (offset, walls[..end - offset].to_vec())
+                })
+                .collect_vec();
+            rows[0].1[0] = false;
+            let mut steps = vec![];
+            let mut path = String::new();
+            for (forward, left) in turns {
+                steps.push(Forward(forward));
+                steps.push(if left { Left } else { Right });
+                path.push_str(&format!("{forward}{}", if left { 'L' } else { 'R' }));
+            }
+            steps.push(Forward(last));
+            path.push_str(&last.to_string());
+            (rows, steps, path)
+        })
+    }
+
+    /// password walking tile by tile, wrapping around to the other side of the map
+    fn walk(rows: &[Row], steps: &[Step]) -> usize {
+        let wall = |row: isize, column: isize| -> Option<bool> {
+            let (offset, walls) = rows.get(usize::try_from(row).ok()?)?;
+            walls
+                .get(usize::try_from(column).ok()?.checked_sub(*offset)?)
+                .copied()
+        };
+        let (mut row, mut column, mut facing) = (0isize, rows[0].0 as isize, 0);
+        for step in steps {
+            match step {
+                Left => facing = (facing + 3) % 4,
+                Right => facing = (facing + 1) % 4,
+                Forward(forward) => {
+                    let (dr, dc) = [(0, 1), (1, 0), (0, -1), (-1, 0)][facing];
+                    for _ in 0..*forward {
+                        let (mut r, mut c) = (row + dr, column + dc);
+                        if wall(r, c).is_none() {
+                            (r, c) = (row, column);
+                            while wall(r - dr, c - dc).is_some() {
+                                (r, c) = (r - dr, c - dc);
+                            }
+                        }
+                        if wall(r, c) == Some(true) {
+                            break;
+                        }
+                        (row, column) = (r, c);
+                    }
+                }
+            }
+        }
+        1000 * (row as usize + 1) + 4 * (column as usize + 1) + facing
+    }
+
+    proptest! {
+        #[test]
+        fn notes_can_be_read((rows, steps, path) in notes()) {
+            let map = rows
+                .iter()
+                .map(|(offset, walls)| {
+                    " ".repeat(*offset) + &walls.iter().map(|w| if *w { '#' } else { '.' }).join("")
+                })
+                .join("\n");
+            let map: Map = format!("{map}\n\n{path}\n").parse().unwrap();
+            prop_assert_eq!(&steps, &map.path);
+            prop_assert_eq!(Answer::from(walk(&rows, &steps)), map.part1().unwrap());
+        }
+    }
 }
@@ -1,4 +1,7 @@
 use crate::day23::Direction::{East, North, South, West};
+use crate::parsing::{at, check_chars, or_parse_error};
+use crate::solution::{Answer, Solution};
+use eyre::bail;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter, Write};
 use std::str::FromStr;
@@ -9,7 +12,8 @@ struct Pos {
     y: isize,
 }
 
-struct Grove {
+#[derive(Clone)]
+pub struct Grove {
     elves: HashSet<Pos>,
     round: usize,
 }
@@ -17,22 +21,24 @@ impl FromStr for Grove {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let elves = s
-            .lines()
-            .enumerate()
-            .flat_map(|(y, l)| {
-                l.chars().enumerate().filter_map(move |(x, c)| {
-                    if c == '#' {
-                        Some(Pos {
+        let mut elves = HashSet::new();
+        for (y, l) in s.lines().enumerate() {
+            for (x, c) in l.trim_end().chars().enumerate() {
+                match c {
+                    '#' => {
+                        elves.insert(Pos {
                             x: x as isize,
                             y: y as isize,
-                        })
-                    } else {
-                        None
+                        });
                     }
-                })
-            })
-            .collect();
+                    '.' => {}
+                    _ => bail!("unexpected '{c}' at {}", at(y, x)),
+                }
+            }
+        }
+        if elves.is_empty() {
+            bail!("no elf in the grove");
+        }
         Ok(Self { elves, round: 0 })
     }
 }
@@ -76,7 +82,7 @@ impl Grove {
     }
 
     // return an updated Grove and the number of moves
-    fn next_round(self) -> (Self, usize) {
+    pub fn next_round(self) -> (Self, usize) {
         let tentative_next_pos: Vec<Pos> = self
             .elves
             .iter()
@@ -115,7 +121,7 @@ impl Grove {
         let elves = self
             .elves
             .iter()
-            .zip(tentative_next_pos.into_iter())
+            .zip(tentative_next_pos)
             .map(|(previous, next)| {
                 if *count_by_next_pos.get(&next).unwrap() > 1 {
                     *previous
@@ -136,7 +142,7 @@ impl Grove {
         )
     }
 
-    fn count_empty_ground(&self) -> usize {
+    pub fn count_empty_ground(&self) -> usize {
         let min_x = self.elves.iter().map(|p| p.x).min().unwrap();
         let max_x = self.elves.iter().map(|p| p.x).max().unwrap();
         let min_y = self.elves.iter().map(|p| p.y).min().unwrap();
@@ -168,34 +174,40 @@ impl Display for Grove {
     }
 }
 
-pub fn plant_grove() {
-    let mut grove: Grove = include_str!("../resources/day23_grove.txt")
-        .parse()
-        .unwrap();
-    for _ in 0..10 {
-        let (new_grove, _) = grove.next_round();
-        grove = new_grove;
+impl Solution<'_> for Grove {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        input.parse()
     }
-    let free_tiles_count = grove.count_empty_ground();
-    println!("free ground after 10 rounds : {free_tiles_count}");
-
-    let mut grove: Grove = include_str!("../resources/day23_grove.txt")
-        .parse()
-        .unwrap();
-    loop {
-        let (new_grove, count) = grove.next_round();
-        grove = new_grove;
-        if count == 0 {
-            break;
+    fn part1(&self) -> eyre::Result<Answer> {
+        let mut grove = self.clone();
+        for _ in 0..10 {
+            let (new_grove, _) = grove.next_round();
+            grove = new_grove;
+        }
+        Ok(grove.count_empty_ground().into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        let mut grove = self.clone();
+        loop {
+            let (new_grove, count) = grove.next_round();
+            grove = new_grove;
+            if count == 0 {
+                break;
+            }
         }
+        Ok(grove.round.into())
+    }
+    fn check(input: &str) -> Vec<eyre::Report> {
+        or_parse_error(check_chars(input, "#."), || input.parse::<Self>())
     }
-    println!("no move after  {} rounds", grove.round);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use itertools::Itertools;
+    use proptest::prelude::*;
 
     #[test]
     fn aoc_example_works() {
@@ -219,6 +231,92 @@ mod tests {
             let (new_grove, _) = grove.next_round();
             grove = new_grove;
         }
-        assert_eq!(110, grove.count_empty_ground())
+        assert_eq!(110, grove.count_empty_ground());
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = "..#\n.o.\n".parse::<Grove>().err().unwrap();
+        assert_eq!("unexpected 'o' at line 2, column 2", error.to_string());
+        assert!("...\n".parse::<Grove>().is_err());
+    }
+
+    /// groves of at most 8 x 8 tiles, with at least an elf
+    fn grove() -> impl Strategy<Value = Vec<Vec<bool>>> {
+        prop::collection::vec(prop::collection::vec(prop::bool::ANY, 1..8), 1..8)
+            .prop_filter("no elf", |rows| rows.iter().flatten().any(|elf| *elf))
+    }
+
+    /// the elves after each round, until none of them moves
+    fn spread(grove: &[Vec<bool>]) -> Vec<HashSet<(isize, isize)>> {
+        let mut elves: HashSet<(isize, isize)> = (0..grove.len())
+            .flat_map(|y| (0..grove[y].len()).map(move |x| (x, y)))
+            .filter(|(x, y)| grove[*y][*x])
+            .map(|(x, y)| (x as isize, y as isize))
+            .collect();
+        let looks = [
+            [(-1, -1), (0, -1), (1, -1)],
+            [(-1, 1), (0, 1), (1, 1)],
+            [(-1, -1), (-1, 0), (-1, 1)],
+            [(1, -1), (1, 0), (1, 1)],
+        ];
+        let mut rounds = vec![elves.clone()];
+        for round in 0.. {
+            let free = |(x, y): (isize, isize), looks: &[(isize, isize)]| {
+                looks
+                    .iter()
+                    .all(|(dx, dy)| !elves.contains(&(x + dx, y + dy)))
+            };
+            let proposals: HashMap<_, _> = elves
+                .iter()
+                .map(|elf| {
+                    let alone = free(*elf, &looks.concat());
+                    let towards = (0..4)
+                        .map(|offset| looks[(round + offset) % 4])
+                        .find(|looks| !alone && free(*elf, looks))
+                        .map_or(*elf, |looks| (elf.0 + looks[1].0, elf.1 + looks[1].1));
+                    (*elf, towards)
+                })
+                .collect();
+            let next: HashSet<_> = proposals
+                .iter()
+                .map(
+                    |(elf, towards)| match proposals.values().filter(|t| *t == towards).count() {
+                        1 => *towards,
+                        _ => *elf,
+                    },
+                )
+                .collect();
+            if next == elves {
+                return rounds;
+            }
+            elves = next;
+            rounds.push(elves.clone());
+        }
+        unreachable!()
+    }
+
+    proptest! {
+        #[test]
+        fn grove_can_be_read(grove in grove()) {
+            let input = grove
+                .iter()
+                .map(|row| row.iter().map(|elf| if *elf { '#' } else { '.' }).collect::<String>())
+                .join("\n");
+            let rounds = spread(&grove);
+            let tenth = &rounds[10.min(rounds.len() - 1)];
+            let width = tenth.iter().map(|e| e.0).max().unwrap() - tenth.iter().map(|e| e.0).min().unwrap() + 1;
+            let height = tenth.iter().map(|e| e.1).max().unwrap() - tenth.iter().map(|e| e.1).min().unwrap() + 1;
+            let read = Grove::parse(&input).unwrap();
+            prop_assert_eq!(Answer::from(width as usize * height as usize - tenth.len()), read.part1().unwrap());
+            prop_assert_eq!(Answer::from(rounds.len()), read.part2().unwrap());
+
+            // displayed after its heading, the grove reads back the same
+            let (moved, _) = read.next_round();
+            let displayed = moved.to_string();
+            let reread: Grove = displayed.split_once('\n').unwrap().1.parse().unwrap();
+            prop_assert_eq!(moved.elves.len(), reread.elves.len());
+            prop_assert_eq!(moved.count_empty_ground(), reread.count_empty_ground());
+        }
     }
 }
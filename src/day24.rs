@@ -1,4 +1,6 @@
-use eyre::eyre;
+use crate::parsing::{at, check_chars, or_parse_error};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre};
 use std::collections::HashSet;
 use std::str::FromStr;
 
@@ -22,7 +24,7 @@ struct Blizzard {
     dir: Direction,
 }
 
-struct Valley {
+pub struct Valley {
     width: usize,
     depth: usize,
     start: Point,
@@ -99,30 +101,42 @@ impl FromStr for Valley {
             .lines()
             .enumerate()
             .flat_map(|(y, l)| {
-                l.chars().enumerate().filter_map(move |(x, c)| match c {
-                    '.' => Some(Item::Point(Point { x, y })),
-                    '^' => Some(Item::Blizz(Blizzard {
-                        start: Point { x, y },
-                        dir: Direction::North,
-                    })),
-                    '>' => Some(Item::Blizz(Blizzard {
-                        start: Point { x, y },
-                        dir: Direction::East,
-                    })),
-                    '<' => Some(Item::Blizz(Blizzard {
-                        start: Point { x, y },
-                        dir: Direction::West,
-                    })),
-                    'v' => Some(Item::Blizz(Blizzard {
-                        start: Point { x, y },
-                        dir: Direction::South,
-                    })),
-                    _ => None,
+                l.trim_end().chars().enumerate().filter_map(move |(x, c)| {
+                    let start = Point { x, y };
+                    match c {
+                        '#' => None,
+                        '.' => Some(Ok(Item::Point(start))),
+                        '^' => Some(Ok(Item::Blizz(Blizzard {
+                            start,
+                            dir: Direction::North,
+                        }))),
+                        '>' => Some(Ok(Item::Blizz(Blizzard {
+                            start,
+                            dir: Direction::East,
+                        }))),
+                        '<' => Some(Ok(Item::Blizz(Blizzard {
+                            start,
+                            dir: Direction::West,
+                        }))),
+                        'v' => Some(Ok(Item::Blizz(Blizzard {
+                            start,
+                            dir: Direction::South,
+                        }))),
+                        _ => Some(Err(eyre!("unexpected '{c}' at {}", at(y, x)))),
+                    }
                 })
             })
-            .collect();
-        let Item::Point(start)= items[0] else {return Err(eyre!("first item should be a point !"))};
-        let Item::Point(exit)= items[items.len()-1] else {return Err(eyre!("last item should be a point !"))};
+            .collect::<eyre::Result<_>>()?;
+        let Some(Item::Point(start)) = items.first() else {
+            return Err(eyre!("first item should be a point !"));
+        };
+        let Some(Item::Point(exit)) = items.last() else {
+            return Err(eyre!("last item should be a point !"));
+        };
+        let (start, exit) = (*start, *exit);
+        if exit.y < 2 {
+            return Err(eyre!("valley has no depth"));
+        }
 
         let blizz = items
             .into_iter()
@@ -155,22 +169,29 @@ fn find_shortest_safe_path(
     start_round: usize,
     start: &Point,
     end: &Point,
-) -> usize {
+) -> eyre::Result<usize> {
     // kind of taboo SINCE we explore all solution, this is useless
     // let period = valley.depth * valley.width;
     // let mut already_explored = HashSet::from([(*start, start_round)]);
     let mut current_pos = vec![*start];
+    // blizzards come back every width * depth rounds : past all (position, round in period) pairs
+    // the end cannot be reached anymore
+    let period = valley.width * valley.depth;
+    let last_round = start_round + period * (period + 2);
 
     let mut round = start_round;
     loop {
         round += 1;
+        if round > last_round {
+            bail!("blizzards always block the way from round {start_round}");
+        }
         let blizz_pos = valley.get_blizz_pos(round);
         let new_pos: HashSet<Point> = current_pos
             .iter()
             .flat_map(|p| valley.get_possible_moves(p, &blizz_pos))
             .collect();
         if new_pos.is_empty() {
-            panic!("no more options : should raise  number of kept options");
+            bail!("no more options from round {round}, the expedition is stuck in blizzards");
         }
         if new_pos.contains(end) {
             break;
@@ -198,34 +219,39 @@ fn find_shortest_safe_path(
         // }
     }
 
-    round
+    Ok(round)
 }
 
-fn find_minimum_round_to_exit(valley: &Valley) -> usize {
+pub fn find_minimum_round_to_exit(valley: &Valley) -> eyre::Result<usize> {
     find_shortest_safe_path(valley, 0, &valley.start, &valley.exit)
 }
 
-fn find_minimum_round_to_exit_with_snack(valley: &Valley) -> usize {
-    let len = find_shortest_safe_path(valley, 0, &valley.start, &valley.exit);
-    let len = find_shortest_safe_path(valley, len, &valley.exit, &valley.start);
+pub fn find_minimum_round_to_exit_with_snack(valley: &Valley) -> eyre::Result<usize> {
+    let len = find_shortest_safe_path(valley, 0, &valley.start, &valley.exit)?;
+    let len = find_shortest_safe_path(valley, len, &valley.exit, &valley.start)?;
     find_shortest_safe_path(valley, len, &valley.start, &valley.exit)
 }
 
-pub fn escape_valley() {
-    let valley: Valley = include_str!("../resources/day24_blizzard_valley.txt")
-        .parse()
-        .unwrap();
-
-    let minimum_escape_rounds = find_minimum_round_to_exit(&valley);
-    println!("goal can be reached in  : {minimum_escape_rounds} rounds");
-    let full_path = find_minimum_round_to_exit_with_snack(&valley);
-    println!("goal can be reached with snack  in  : {full_path} rounds");
+impl Solution<'_> for Valley {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        input.parse()
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(find_minimum_round_to_exit(self)?.into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(find_minimum_round_to_exit_with_snack(self)?.into())
+    }
+    fn check(input: &str) -> Vec<eyre::Report> {
+        or_parse_error(check_chars(input, "#.<>^v"), || input.parse::<Self>())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn aoc_example_works() {
@@ -272,7 +298,105 @@ mod tests {
 
         assert_eq!(blizz_pos_round_0, valley.get_blizz_pos(0));
 
-        assert_eq!(18, find_minimum_round_to_exit(&valley));
-        assert_eq!(54, find_minimum_round_to_exit_with_snack(&valley));
+        assert_eq!(18, find_minimum_round_to_exit(&valley).unwrap());
+        assert_eq!(54, find_minimum_round_to_exit_with_snack(&valley).unwrap());
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = "#.###\n#>x.#\n###.#\n".parse::<Valley>().err().unwrap();
+        assert_eq!("unexpected 'x' at line 2, column 3", error.to_string());
+    }
+
+    #[test]
+    fn blocked_valleys_are_errors() {
+        let valley: Valley = "#.#\n#>#\n#.#\n".parse().unwrap();
+        let error = find_minimum_round_to_exit(&valley).err().unwrap();
+        assert_eq!(
+            "blizzards always block the way from round 0",
+            error.to_string()
+        );
+    }
+
+    /// valleys of at most 5 x 4 grounds, between the entrance and the exit walls
+    fn valley() -> impl Strategy<Value = Vec<String>> {
+        (1..6usize, 1..5usize)
+            .prop_flat_map(|(width, depth)| {
+                let grounds = prop::collection::vec(
+                    prop::sample::select(vec!['.', '<', '>', '^', 'v']),
+                    width,
+                );
+                (Just(width), prop::collection::vec(grounds, depth))
+            })
+            .prop_map(|(width, grounds)| {
+                let mut valley = vec![format!("#.{}", "#".repeat(width))];
+                for row in grounds {
+                    valley.push(format!("#{}#", row.into_iter().collect::<String>()));
+                }
+                valley.push(format!("{}.#", "#".repeat(width)));
+                valley
+            })
+    }
+
+    /// the first round the expedition can reach `to`, none when blizzards always block the way
+    fn crossing(
+        valley: &[String],
+        round: usize,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Option<usize> {
+        let (width, depth) = (valley[0].len() - 2, valley.len() - 2);
+        let blizzards = |round: usize| -> HashSet<(usize, usize)> {
+            (1..=depth)
+                .flat_map(|y| (1..=width).map(move |x| (x, y)))
+                .filter_map(|(x, y)| match valley[y].as_bytes()[x] {
+                    b'>' => Some((1 + (x - 1 + round) % width, y)),
+                    b'<' => Some((1 + (x - 1 + width * round - round) % width, y)),
+                    b'v' => Some((x, 1 + (y - 1 + round) % depth)),
+                    b'^' => Some((x, 1 + (y - 1 + depth * round - round) % depth)),
+                    _ => None,
+                })
+                .collect()
+        };
+        let mut expedition = HashSet::from([from]);
+        for round in round + 1..=round + (width * depth) * (width * depth + 2) {
+            let blizzards = blizzards(round);
+            expedition = expedition
+                .iter()
+                .flat_map(|&(x, y)| {
+                    [
+                        (x, y),
+                        (x - 1, y),
+                        (x + 1, y),
+                        (x, y.wrapping_sub(1)),
+                        (x, y + 1),
+                    ]
+                })
+                .filter(|&(x, y)| {
+                    (x, y) == from
+                        || (x, y) == to
+                        || (1..=width).contains(&x) && (1..=depth).contains(&y)
+                })
+                .filter(|ground| !blizzards.contains(ground))
+                .collect();
+            if expedition.contains(&to) {
+                return Some(round);
+            }
+        }
+        None
+    }
+
+    proptest! {
+        #[test]
+        fn valley_can_be_crossed(valley in valley()) {
+            let (entrance, exit) = ((1, 0), (valley[0].len() - 2, valley.len() - 1));
+            let there = crossing(&valley, 0, entrance, exit);
+            let back = there.and_then(|round| crossing(&valley, round, exit, entrance));
+            let there_again = back.and_then(|round| crossing(&valley, round, entrance, exit));
+
+            let valley = Valley::parse(&valley.join("\n")).unwrap();
+            prop_assert_eq!(there.map(Answer::from), valley.part1().ok());
+            prop_assert_eq!(there_again.map(Answer::from), valley.part2().ok());
+        }
     }
 }
@@ -1,9 +1,11 @@
-use eyre::eyre;
+use crate::parsing::{check_lines, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, WrapErr};
 use std::fmt::{Debug, Display, Formatter, Write};
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
-enum SnafuDigit {
+pub enum SnafuDigit {
     Zero = 0,
     One = 1,
     Two = 2,
@@ -55,10 +57,10 @@ impl Display for SnafuDigit {
     }
 }
 
-const SNAFU_DIGIT_VALUES_COUNT: usize = 5;
+const SNAFU_DIGIT_VALUES_COUNT: isize = 5;
 
 #[derive(Debug, Eq, PartialEq)]
-struct SnafuNumber {
+pub struct SnafuNumber {
     digits: Vec<SnafuDigit>,
 }
 impl Display for SnafuNumber {
@@ -74,14 +76,26 @@ impl FromStr for SnafuNumber {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let digits: Result<Vec<SnafuDigit>, _> = s.chars().rev().map(|c| c.try_into()).collect();
-        let digits = digits?;
+        let s = s.trim();
+        if s.is_empty() {
+            bail!("no SNAFU digit");
+        }
+        let digits: Result<Vec<SnafuDigit>, _> = s
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                c.try_into()
+                    .wrap_err_with(|| format!("at column {}", i + 1))
+            })
+            .collect();
+        let mut digits = digits?;
+        digits.reverse();
         Ok(Self { digits })
     }
 }
 
-impl From<usize> for SnafuNumber {
-    fn from(value: usize) -> Self {
+impl From<isize> for SnafuNumber {
+    fn from(value: isize) -> Self {
         if value == 0 {
             return Self {
                 digits: vec![SnafuDigit::Zero],
@@ -89,60 +103,100 @@ impl From<usize> for SnafuNumber {
         }
         let mut digits = vec![];
         let mut remaining = value;
-        let mut carry = 0isize;
-        while remaining > 0 || carry != 0 {
-            let mut digit = (remaining % SNAFU_DIGIT_VALUES_COUNT) as isize + carry;
-            carry = 0;
+        while remaining != 0 {
+            // 3 and 4 are written -2 and -1, the next digit getting the carry
+            let mut digit = remaining.rem_euclid(SNAFU_DIGIT_VALUES_COUNT);
+            remaining = remaining.div_euclid(SNAFU_DIGIT_VALUES_COUNT);
             if digit > 2 {
-                carry = 1;
-                digit -= 5;
+                digit -= SNAFU_DIGIT_VALUES_COUNT;
+                remaining += 1;
             }
-            remaining /= 5;
             digits.push(digit.try_into().expect("digit MUST be in [-2,2] here !"));
         }
         Self { digits }
     }
 }
 
-impl From<&SnafuNumber> for isize {
-    fn from(value: &SnafuNumber) -> Self {
-        let mut result = 0;
-        let mut factor = 1;
-        for digit in &value.digits {
-            result += factor * *digit as isize;
-            factor *= 5;
-        }
-        result
+impl TryFrom<&SnafuNumber> for isize {
+    type Error = eyre::Error;
+
+    fn try_from(value: &SnafuNumber) -> Result<Self, Self::Error> {
+        value
+            .digits
+            .iter()
+            .rev()
+            .try_fold(0isize, |result, digit| {
+                result
+                    .checked_mul(SNAFU_DIGIT_VALUES_COUNT)?
+                    .checked_add(*digit as isize)
+            })
+            .ok_or_else(|| eyre!("{value} does not fit in an isize"))
     }
 }
 
-fn sum_snafus(snafus: &str) -> SnafuNumber {
-    let sum: isize = snafus
-        .lines()
-        .filter_map(|l| l.parse::<SnafuNumber>().ok())
-        .map(|snafu| isize::from(&snafu))
-        .sum();
-    assert!(sum >= 0, "negative number snafu repr not implemented yet !");
+fn sum(numbers: &[SnafuNumber]) -> eyre::Result<SnafuNumber> {
+    let mut sum = 0isize;
+    for number in numbers {
+        sum = sum
+            .checked_add(isize::try_from(number)?)
+            .ok_or_else(|| eyre!("the sum does not fit in an isize"))?;
+    }
+    Ok(SnafuNumber::from(sum))
+}
 
-    SnafuNumber::from(sum as usize)
+pub fn sum_snafus(snafus: &str) -> eyre::Result<SnafuNumber> {
+    sum(&parse_lines(snafus, str::parse)?)
 }
 
-pub fn calibrate_bob() {
-    let snafus = include_str!("../resources/day25_snafus.txt");
-    let sum = sum_snafus(snafus);
+pub struct FuelRequirements {
+    numbers: Vec<SnafuNumber>,
+}
 
-    println!("type {sum} to calibrate Bob");
+impl Solution<'_> for FuelRequirements {
+    fn parse(snafus: &str) -> eyre::Result<Self> {
+        Ok(Self {
+            numbers: parse_lines(snafus, str::parse)?,
+        })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(sum(&self.numbers)?.to_string().into())
+    }
+    /// day 25 only has one puzzle, the second star being granted by the 49 others
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(Answer::Empty)
+    }
+    fn check(snafus: &str) -> Vec<eyre::Report> {
+        check_lines(snafus, str::parse::<SnafuNumber>)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn snafu_round_trips(number in any::<isize>()) {
+            let snafu = SnafuNumber::from(number);
+            prop_assert_eq!(number, isize::try_from(&snafu).unwrap());
+            prop_assert_eq!(snafu, SnafuNumber::from(number).to_string().parse().unwrap());
+        }
+
+        #[test]
+        fn snafu_parsing_does_not_panic(s in "[012=\\-3 ]{0,40}") {
+            if let Ok(snafu) = s.parse::<SnafuNumber>() {
+                let _ = isize::try_from(&snafu);
+            }
+        }
+    }
 
     #[test]
     fn snafu_can_be_converted() {
         let snafu = SnafuNumber::from(99);
-        assert_eq!(isize::from(&snafu), 99);
+        assert_eq!(isize::try_from(&snafu).unwrap(), 99);
+        assert_eq!("-2", SnafuNumber::from(-3).to_string());
         let numbers: Vec<isize> = vec![
             1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 15, 20, 2022, 12345, 314159265, 1747, 906, 198, 11, 201,
             31, 1257, 32, 353, 107, 7, 3, 37,
@@ -178,17 +232,17 @@ mod tests {
             122
         "}
         .lines()
-        .filter_map(|l| l.parse().ok())
+        .map(|l| l.parse().unwrap())
         .collect();
         assert_eq!(numbers.len(), snafus.len());
-        for (number, snafu) in numbers.into_iter().zip(snafus.into_iter()) {
-            let from_snafu = isize::from(&snafu);
+        for (number, snafu) in numbers.into_iter().zip(snafus) {
+            let from_snafu = isize::try_from(&snafu).unwrap();
             assert_eq!(
                 number, from_snafu,
                 "{snafu} converted to {from_snafu} instead of {number}"
             );
 
-            let to_snafu = SnafuNumber::from(number as usize);
+            let to_snafu = SnafuNumber::from(number);
             assert_eq!(
                 to_snafu, snafu,
                 "{number} converted to {to_snafu} instead of {snafu}"
@@ -213,8 +267,20 @@ mod tests {
             1=
             122
         "};
-        let sum = sum_snafus(input);
-        assert_eq!(isize::from(&sum), 4890);
+        let sum = sum_snafus(input).unwrap();
+        assert_eq!(isize::try_from(&sum).unwrap(), 4890);
         assert_eq!(sum, "2=-1=0".parse::<SnafuNumber>().unwrap());
+        assert_eq!("-", sum_snafus("-\n").unwrap().to_string());
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = sum_snafus("1=-0-2\n12311\n").unwrap_err();
+        assert_eq!("line 2 : '12311'", error.to_string());
+        assert_eq!(
+            "at column 3",
+            "12311".parse::<SnafuNumber>().unwrap_err().to_string()
+        );
+        assert!(sum_snafus(&"2".repeat(30)).is_err());
     }
 }
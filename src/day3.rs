@@ -1,63 +1,218 @@
-use std::collections::HashSet;
+use crate::parsing::{check_lines, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, WrapErr};
+use std::str::FromStr;
 
-fn get_priority(item_type: char) -> usize {
+pub fn get_priority(item_type: char) -> usize {
     match item_type {
-        c if ('a'..='z').contains(&c) => c as usize - 'a' as usize + 1,
-        c if ('A'..='Z').contains(&c) => c as usize - 'A' as usize + 27,
+        c if c.is_ascii_lowercase() => c as usize - 'a' as usize + 1,
+        c if c.is_ascii_uppercase() => c as usize - 'A' as usize + 27,
         _ => 0,
     }
 }
-fn get_rucksack_unranged_item_type(rucksack: &str) -> Option<char> {
+
+fn get_item_type(priority: usize) -> char {
+    match priority {
+        1..=26 => (b'a' + (priority - 1) as u8) as char,
+        _ => (b'A' + (priority - 27) as u8) as char,
+    }
+}
+
+/// a set of item types, the bit `get_priority(c) - 1` being set for each type `c`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemTypes(u64);
+
+impl ItemTypes {
+    pub const ALL: ItemTypes = ItemTypes((1 << 52) - 1);
+
+    pub fn contains(self, item_type: char) -> bool {
+        let priority = get_priority(item_type);
+        priority > 0 && self.0 & (1 << (priority - 1)) != 0
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// priorities of the item types, lowest first
+    pub fn priorities(self) -> impl Iterator<Item = usize> {
+        (1..=52).filter(move |p| self.0 & (1 << (p - 1)) != 0)
+    }
+
+    pub fn item_types(self) -> impl Iterator<Item = char> {
+        self.priorities().map(get_item_type)
+    }
+}
+
+impl FromStr for ItemTypes {
+    type Err = eyre::Error;
+
+    fn from_str(items: &str) -> eyre::Result<Self> {
+        let mut mask = 0;
+        for (i, c) in items.char_indices() {
+            match get_priority(c) {
+                0 => bail!("invalid item type '{c}' at column {}", i + 1),
+                p => mask |= 1 << (p - 1),
+            }
+        }
+        Ok(Self(mask))
+    }
+}
+
+/// item types found in all of `sets`
+pub fn shared_item_types(sets: impl IntoIterator<Item = ItemTypes>) -> ItemTypes {
+    sets.into_iter()
+        .reduce(ItemTypes::intersection)
+        .unwrap_or_default()
+}
+
+/// item types of each of the `compartments` a rucksack is evenly split in
+pub fn read_compartments(rucksack: &str, compartments: usize) -> eyre::Result<Vec<ItemTypes>> {
+    rucksack.parse::<ItemTypes>()?;
     let len = rucksack.len();
-    assert_eq!(0, len % 2, "rucksacks must be of even size");
-    let lefties: HashSet<_> = rucksack[0..len / 2].chars().collect();
-    let righties: HashSet<_> = rucksack[len / 2..len].chars().collect();
+    if compartments == 0 || !len.is_multiple_of(compartments) {
+        bail!("{len} items cannot be split in {compartments} compartments");
+    }
+    if len == 0 {
+        return Ok(vec![ItemTypes::default(); compartments]);
+    }
+    (0..len)
+        .step_by(len / compartments)
+        .map(|start| rucksack[start..start + len / compartments].parse())
+        .collect()
+}
+
+pub fn get_rucksack_unranged_item_type(rucksack: &str) -> Option<char> {
+    let compartments = read_compartments(rucksack, 2).ok()?;
+    shared_item_types(compartments).item_types().next()
+}
+
+/// checks a rucksack only holds item types, evenly split in its two compartments
+pub fn read_rucksack(rucksack: &str) -> eyre::Result<&str> {
+    read_compartments(rucksack, 2)?;
+    Ok(rucksack)
+}
 
-    lefties.intersection(&righties).next().copied()
+/// item types shared by the compartments of each rucksack
+pub fn unarranged_item_types(rucksacks: &str, compartments: usize) -> eyre::Result<Vec<ItemTypes>> {
+    parse_lines(rucksacks, |r| {
+        Ok(shared_item_types(read_compartments(r, compartments)?))
+    })
 }
 
-fn sum_unarranged_item_type(rucksacks: &str) -> usize {
-    rucksacks
-        .lines()
-        .filter_map(get_rucksack_unranged_item_type)
-        .map(get_priority)
-        .sum()
+/// priority of the single item type of `shared`, a `noun` found `place`
+fn single_priority(shared: ItemTypes, noun: &str, place: &str) -> eyre::Result<usize> {
+    match shared.len() {
+        0 => bail!("no {noun} {place}"),
+        1 => Ok(shared.priorities().next().expect("one item type")),
+        n => bail!(
+            "{n} {noun}s {place} : {}",
+            shared.item_types().collect::<String>()
+        ),
+    }
 }
 
-fn sum_groups_badge_priorities(rucksacks: &str) -> usize {
+/// sum of the priorities of the item type found in both compartments of each rucksack
+pub fn sum_misplaced_priorities(rucksacks: &[Vec<ItemTypes>]) -> eyre::Result<usize> {
     let mut sum = 0;
-    let mut lines = rucksacks.lines();
-    while let (Some(first), Some(second), Some(third)) = (lines.next(), lines.next(), lines.next())
-    {
-        let firsts: HashSet<_> = first.chars().collect();
-        let seconds: HashSet<_> = second.chars().collect();
-        let thirds: HashSet<_> = third.chars().collect();
+    for (i, compartments) in rucksacks.iter().enumerate() {
+        let shared = shared_item_types(compartments.iter().copied());
+        sum += single_priority(shared, "item type", "in both compartments")
+            .wrap_err_with(|| format!("rucksack {}", i + 1))?;
+    }
+    Ok(sum)
+}
 
-        let first_and_seconds: HashSet<_> = firsts.intersection(&seconds).copied().collect();
+pub fn sum_unarranged_item_type(rucksacks: &str) -> eyre::Result<usize> {
+    sum_misplaced_priorities(&parse_lines(rucksacks, |r| read_compartments(r, 2))?)
+}
 
-        sum += first_and_seconds
-            .intersection(&thirds)
-            .next()
-            .copied()
-            .map(get_priority)
-            .unwrap_or(0);
+/// item types carried by all the `rucksacks` of each group of `group_size` elves
+pub fn badges(rucksacks: &[ItemTypes], group_size: usize) -> eyre::Result<Vec<ItemTypes>> {
+    if group_size == 0 || !rucksacks.len().is_multiple_of(group_size) {
+        bail!(
+            "{} rucksacks cannot be split in groups of {group_size}",
+            rucksacks.len()
+        );
     }
-    sum
+    Ok(rucksacks
+        .chunks(group_size)
+        .map(|group| shared_item_types(group.iter().copied()))
+        .collect())
 }
 
-pub fn arrange_ruckacks() {
-    let rucksacks = include_str!("../resources/day3_rucksacks.txt");
-    let unarranged_sum = sum_unarranged_item_type(rucksacks);
-    println!("sum of the unarranged item types priorities : {unarranged_sum}");
+/// [badges] of the rucksacks listed one per line
+pub fn group_badges(rucksacks: &str, group_size: usize) -> eyre::Result<Vec<ItemTypes>> {
+    badges(&parse_lines(rucksacks, str::parse)?, group_size)
+}
 
-    let badges_sum = sum_groups_badge_priorities(rucksacks);
-    println!("sum of the badges priorities : {badges_sum}");
+/// sum of the priorities of the single badge of each group of 3 `rucksacks`
+pub fn sum_badge_priorities(rucksacks: &[ItemTypes]) -> eyre::Result<usize> {
+    let mut sum = 0;
+    for (group, badges) in badges(rucksacks, 3)?.into_iter().enumerate() {
+        sum += single_priority(badges, "badge", &format!("for group {}", group + 1))?;
+    }
+    Ok(sum)
+}
+
+pub fn sum_groups_badge_priorities(rucksacks: &str) -> eyre::Result<usize> {
+    let rucksacks = parse_lines(rucksacks, |r| read_rucksack(r)?.parse())?;
+    sum_badge_priorities(&rucksacks)
+}
+
+pub struct Rucksacks {
+    /// item types of the two compartments of each rucksack
+    compartments: Vec<Vec<ItemTypes>>,
+}
+
+impl<'i> Solution<'i> for Rucksacks {
+    fn parse(rucksacks: &'i str) -> eyre::Result<Self> {
+        Ok(Self {
+            compartments: parse_lines(rucksacks, |r| read_compartments(r, 2))?,
+        })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(sum_misplaced_priorities(&self.compartments)?.into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        let rucksacks: Vec<ItemTypes> = self
+            .compartments
+            .iter()
+            .map(|c| {
+                c.iter()
+                    .copied()
+                    .fold(ItemTypes::default(), ItemTypes::union)
+            })
+            .collect();
+        Ok(sum_badge_priorities(&rucksacks)?.into())
+    }
+    fn check(rucksacks: &str) -> Vec<eyre::Report> {
+        let mut problems = check_lines(rucksacks, read_rucksack);
+        let count = rucksacks.lines().filter(|l| !l.trim().is_empty()).count();
+        if !count.is_multiple_of(3) {
+            problems.push(eyre!("{count} rucksacks cannot be split in groups of 3"));
+        }
+        problems
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn char_can_be_converted_to_preiorities() {
@@ -65,17 +220,116 @@ mod tests {
         assert_eq!(get_priority('B'), 28);
     }
 
+    const RUCKSACKS: &str = indoc! {"\
+        vJrwpWtwJgWrhcsFMMfFFhFp
+        jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+        PmmdzqPrVvPwwTWBwg
+        wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+        ttgJtRGJQctTZtZT
+        CrZsJsPPZsGzwwsLwLmpwMDw
+    "};
+
     #[test]
     fn aoc_example_works() {
-        let rucksacks = indoc! {"\
-            vJrwpWtwJgWrhcsFMMfFFhFp
-            jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-            PmmdzqPrVvPwwTWBwg
-            wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-            ttgJtRGJQctTZtZT
-            CrZsJsPPZsGzwwsLwLmpwMDw
-        "};
-        assert_eq!(sum_unarranged_item_type(rucksacks), 157);
-        assert_eq!(sum_groups_badge_priorities(rucksacks), 70);
+        assert_eq!(sum_unarranged_item_type(RUCKSACKS).unwrap(), 157);
+        assert_eq!(sum_groups_badge_priorities(RUCKSACKS).unwrap(), 70);
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        assert!(sum_unarranged_item_type("vJrwpWtwJgWrhcsFMMfFFhF").is_err());
+        assert!(sum_unarranged_item_type("vJrwpWtwJgWr-csFMMfFFhFp").is_err());
+    }
+
+    #[test]
+    fn item_type_sets_work() {
+        let shared = unarranged_item_types("abcXYZabcxyz\n", 2).unwrap();
+        assert_eq!(
+            vec!['a', 'b', 'c'],
+            shared[0].item_types().collect::<Vec<_>>()
+        );
+        let shared = unarranged_item_types("aAbAcAdAeA\n", 5).unwrap();
+        assert_eq!(vec!['A'], shared[0].item_types().collect::<Vec<_>>());
+        assert!(unarranged_item_types("abcd\n", 3).is_err());
+
+        let badges = group_badges(RUCKSACKS, 2).unwrap();
+        assert_eq!(3, badges.len());
+        assert!(badges[0].contains('r') && badges[0].contains('s'));
+        assert_eq!(
+            vec![18],
+            group_badges(RUCKSACKS, 3).unwrap()[0]
+                .priorities()
+                .collect::<Vec<_>>()
+        );
+        assert!(group_badges(RUCKSACKS, 6).unwrap()[0].is_empty());
+        assert!(group_badges(RUCKSACKS, 4).is_err());
+        assert_eq!(
+            ItemTypes::ALL,
+            "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ"
+                .parse()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn several_shared_item_types_are_errors() {
+        assert_eq!(
+            "rucksack 1",
+            sum_unarranged_item_type("abcXYZabcxyz")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "3 item types in both compartments : abc",
+            sum_unarranged_item_type("abcXYZabcxyz")
+                .unwrap_err()
+                .root_cause()
+                .to_string()
+        );
+        assert_eq!(
+            "2 badges for group 1 : ab",
+            sum_groups_badge_priorities("abcd\nabef\nabgh\n")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    /// rucksacks with a single item type in both compartments, and its priority
+    fn rucksacks() -> impl Strategy<Value = (Vec<usize>, String)> {
+        let rucksack = (
+            Just((1..=52).collect::<Vec<usize>>()).prop_shuffle(),
+            prop::collection::vec((0..25usize, 0..26usize), 0..12),
+        )
+            .prop_map(|(priorities, picks)| {
+                let (shared, others) = (priorities[0], &priorities[1..]);
+                let (left, right) = others.split_at(25);
+                let mut rucksack: String =
+                    picks.iter().map(|(l, _)| get_item_type(left[*l])).collect();
+                rucksack.push(get_item_type(shared));
+                rucksack.push(get_item_type(shared));
+                rucksack.extend(picks.iter().map(|(_, r)| get_item_type(right[*r])));
+                (shared, rucksack)
+            });
+        prop::collection::vec(rucksack, 1..20).prop_map(|rucksacks| {
+            let priorities = rucksacks.iter().map(|(p, _)| *p).collect();
+            let lines = rucksacks.iter().map(|(_, r)| format!("{r}\n")).collect();
+            (priorities, lines)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn rucksacks_can_be_read((priorities, rucksacks) in rucksacks()) {
+            prop_assert_eq!(priorities.iter().sum::<usize>(), sum_unarranged_item_type(&rucksacks).unwrap());
+            let sets: Vec<ItemTypes> = rucksacks.lines().map(|r| r.parse().unwrap()).collect();
+            for (set, rucksack) in sets.iter().zip(rucksacks.lines()) {
+                prop_assert_eq!(*set, set.item_types().collect::<String>().parse::<ItemTypes>().unwrap());
+                prop_assert!(rucksack.chars().all(|c| set.contains(c)));
+            }
+            for (group, badges) in sets.chunks(2).zip(badges(&sets[..sets.len() / 2 * 2], 2).unwrap()) {
+                let common = group[0].item_types().filter(|c| group[1].contains(*c)).collect::<String>();
+                prop_assert_eq!(common, badges.item_types().collect::<String>());
+            }
+        }
     }
 }
@@ -1,7 +1,12 @@
-use eyre::{eyre, Context};
+use crate::parsing::{check_lines, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, Context, ContextCompat};
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 
-struct CleanupRange {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CleanupRange {
     start: usize,
     end: usize,
 }
@@ -28,48 +33,314 @@ impl FromStr for CleanupRange {
             .next()
             .ok_or(eyre!("no end data"))
             .and_then(|s| s.parse().context("trying to parse range end"))?;
-        assert!(start <= end);
+        if start > end {
+            bail!("range start {start} is after its end {end}");
+        }
         Ok(Self { start, end })
     }
 }
 
-fn count_overlapping_ranges(
-    assignments: &str,
+/// sections as sorted, disjoint and non adjacent inclusive ranges
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SectionSet {
+    ranges: Vec<(usize, usize)>,
+}
+
+impl SectionSet {
+    fn normalized(mut ranges: Vec<(usize, usize)>) -> Self {
+        ranges.sort_unstable();
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some((_, last_end)) if start <= last_end.saturating_add(1) => {
+                    *last_end = (*last_end).max(end)
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+        Self { ranges: merged }
+    }
+
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<usize>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..=*end)
+    }
+
+    /// number of sections
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|(start, end)| end - start + 1).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, section: usize) -> bool {
+        let i = self.ranges.partition_point(|(_, end)| *end < section);
+        self.ranges
+            .get(i)
+            .is_some_and(|(start, _)| *start <= section)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalized(self.ranges.iter().chain(&other.ranges).copied().collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while let (Some((s1, e1)), Some((s2, e2))) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (start, end) = (*s1.max(s2), *e1.min(e2));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            // the range ending first cannot meet any further range of the other set
+            if e1 < e2 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement(self.span()))
+    }
+
+    /// the sections of `span` missing from this set
+    fn complement(&self, span: Option<(usize, usize)>) -> Self {
+        let Some((span_start, span_end)) = span else {
+            return Self::default();
+        };
+        let mut ranges = vec![];
+        // first section of span not yet known to be in or out of the set
+        let mut next = Some(span_start);
+        for (start, end) in &self.ranges {
+            let Some(missing) = next.filter(|n| n <= &span_end) else {
+                break;
+            };
+            if *start > missing {
+                ranges.push((missing, (start - 1).min(span_end)));
+            }
+            next = end.checked_add(1).map(|n| n.max(missing));
+        }
+        if let Some(missing) = next.filter(|n| n <= &span_end) {
+            ranges.push((missing, span_end));
+        }
+        Self { ranges }
+    }
+
+    /// first and last sections
+    fn span(&self) -> Option<(usize, usize)> {
+        Some((self.ranges.first()?.0, self.ranges.last()?.1))
+    }
+
+    /// sections missing between the first and the last one
+    pub fn gaps(&self) -> Self {
+        self.complement(self.span())
+    }
+}
+
+impl From<&CleanupRange> for SectionSet {
+    fn from(range: &CleanupRange) -> Self {
+        Self {
+            ranges: vec![(range.start, range.end)],
+        }
+    }
+}
+
+impl<'r> FromIterator<&'r CleanupRange> for SectionSet {
+    fn from_iter<T: IntoIterator<Item = &'r CleanupRange>>(ranges: T) -> Self {
+        Self::normalized(ranges.into_iter().map(|r| (r.start, r.end)).collect())
+    }
+}
+
+/// written as in assignments, such as `2-4,6-8`
+impl Display for SectionSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|(start, end)| format!("{start}-{end}"));
+        f.write_str(&ranges.collect::<Vec<_>>().join(","))
+    }
+}
+
+/// sections cleaned by `elves` elves or more, across all pairs
+pub fn sections_cleaned_by(pairs: &[(CleanupRange, CleanupRange)], elves: usize) -> SectionSet {
+    if elves == 0 {
+        return pairs.iter().flat_map(|(l, r)| [l, r]).collect();
+    }
+    // +1 elf where a range starts, -1 after its end
+    let mut changes: Vec<(usize, isize)> = pairs
+        .iter()
+        .flat_map(|(l, r)| [l, r])
+        .flat_map(|r| [(r.start, 1), (r.end.saturating_add(1), -1)])
+        .collect();
+    changes.sort_unstable();
+    let mut ranges = vec![];
+    let mut cleaners = 0isize;
+    let mut start = None;
+    for (section, change) in changes {
+        cleaners += change;
+        match start {
+            None if cleaners >= elves as isize => start = Some(section),
+            Some(s) if cleaners < elves as isize => {
+                ranges.push((s, section - 1));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    SectionSet::normalized(ranges)
+}
+
+/// sections between the first and the last assigned one that nobody cleans
+pub fn uncleaned_sections(pairs: &[(CleanupRange, CleanupRange)]) -> SectionSet {
+    sections_cleaned_by(pairs, 1).gaps()
+}
+
+fn read_pair(line: &str) -> eyre::Result<(CleanupRange, CleanupRange)> {
+    let (left, right) = line.split_once(',').context("no ',' between ranges")?;
+    Ok((left.parse()?, right.parse()?))
+}
+
+pub fn read_pairs(assignments: &str) -> eyre::Result<Vec<(CleanupRange, CleanupRange)>> {
+    parse_lines(assignments, read_pair)
+}
+
+fn count_pairs(
+    pairs: &[(CleanupRange, CleanupRange)],
     filter: impl Fn(&CleanupRange, &CleanupRange) -> bool,
 ) -> usize {
-    assignments
-        .lines()
-        .filter_map(|l| {
-            let mut ranges = l.split(',');
-            let left: Option<CleanupRange> = ranges.next().and_then(|r| r.parse().ok());
-            let right: Option<CleanupRange> = ranges.next().and_then(|r| r.parse().ok());
-            left.and_then(|l| right.map(|r| (l, r)))
-        })
-        .filter(|(l, r)| filter(l, r))
-        .count()
+    pairs.iter().filter(|(l, r)| filter(l, r)).count()
 }
 
-fn count_fully_overlapping_ranges(assignments: &str) -> usize {
-    count_overlapping_ranges(assignments, |l, r| l.contains(r) || r.contains(l))
+fn fully_overlap(l: &CleanupRange, r: &CleanupRange) -> bool {
+    l.contains(r) || r.contains(l)
 }
 
-fn count_partially_overlapping_ranges(assignments: &str) -> usize {
-    count_overlapping_ranges(assignments, |l, r| l.overlaps(r))
+fn partially_overlap(l: &CleanupRange, r: &CleanupRange) -> bool {
+    l.overlaps(r)
 }
 
-pub fn clean_camp() {
-    let assignments = include_str!("../resources/day4_assignments.txt");
-    let full_overlap_count = count_fully_overlapping_ranges(assignments);
-    println!("there are {full_overlap_count} fully overlapping assignments");
+pub fn count_overlapping_ranges(
+    assignments: &str,
+    filter: impl Fn(&CleanupRange, &CleanupRange) -> bool,
+) -> eyre::Result<usize> {
+    Ok(count_pairs(&read_pairs(assignments)?, filter))
+}
 
-    let partial_overlap_count = count_partially_overlapping_ranges(assignments);
-    println!("there are {partial_overlap_count} partially overlapping assignments");
+pub fn count_fully_overlapping_ranges(assignments: &str) -> eyre::Result<usize> {
+    count_overlapping_ranges(assignments, fully_overlap)
+}
+
+pub fn count_partially_overlapping_ranges(assignments: &str) -> eyre::Result<usize> {
+    count_overlapping_ranges(assignments, partially_overlap)
+}
+
+pub struct Assignments {
+    pairs: Vec<(CleanupRange, CleanupRange)>,
+}
+
+impl Solution<'_> for Assignments {
+    fn parse(assignments: &str) -> eyre::Result<Self> {
+        Ok(Self {
+            pairs: read_pairs(assignments)?,
+        })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(count_pairs(&self.pairs, fully_overlap).into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(count_pairs(&self.pairs, partially_overlap).into())
+    }
+    fn check(assignments: &str) -> Vec<eyre::Report> {
+        check_lines(assignments, read_pair)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    fn section_range() -> impl Strategy<Value = (usize, usize)> {
+        (1..100usize, 0..20usize).prop_map(|(start, len)| (start, start + len))
+    }
+
+    fn section_set() -> impl Strategy<Value = (Vec<(CleanupRange, CleanupRange)>, HashSet<usize>)> {
+        prop::collection::vec((section_range(), section_range()), 0..6).prop_map(|pairs| {
+            let sections = pairs
+                .iter()
+                .flat_map(|(l, r)| [*l, *r])
+                .flat_map(|(s, e)| s..=e)
+                .collect();
+            let pairs = pairs
+                .into_iter()
+                .map(|((s1, e1), (s2, e2))| {
+                    (
+                        CleanupRange { start: s1, end: e1 },
+                        CleanupRange { start: s2, end: e2 },
+                    )
+                })
+                .collect();
+            (pairs, sections)
+        })
+    }
+
+    fn sections(set: &SectionSet) -> HashSet<usize> {
+        set.ranges().flatten().collect()
+    }
+
+    proptest! {
+        #[test]
+        fn section_sets_match_hash_sets((pairs1, s1) in section_set(), (pairs2, s2) in section_set()) {
+            let set1 = sections_cleaned_by(&pairs1, 1);
+            let set2: SectionSet = pairs2.iter().flat_map(|(l, r)| [l, r]).collect();
+            prop_assert_eq!(&s1, &sections(&set1));
+            prop_assert_eq!(s1.len(), set1.len());
+            prop_assert_eq!(s1.union(&s2).copied().collect::<HashSet<_>>(), sections(&set1.union(&set2)));
+            prop_assert_eq!(s1.intersection(&s2).copied().collect::<HashSet<_>>(), sections(&set1.intersection(&set2)));
+            prop_assert_eq!(s1.difference(&s2).copied().collect::<HashSet<_>>(), sections(&set1.difference(&set2)));
+            let gaps = match (s1.iter().min(), s1.iter().max()) {
+                (Some(min), Some(max)) => (*min..=*max).filter(|s| !s1.contains(s)).collect(),
+                _ => HashSet::new(),
+            };
+            prop_assert_eq!(gaps, sections(&uncleaned_sections(&pairs1)));
+            for s in 0..130 {
+                prop_assert_eq!(s1.contains(&s), set1.contains(s));
+            }
+
+            let cleaners = |s: usize| pairs1.iter().flat_map(|(l, r)| [l, r]).filter(|r| (r.start..=r.end).contains(&s)).count();
+            let crowded: HashSet<usize> = s1.iter().copied().filter(|s| cleaners(*s) > 2).collect();
+            prop_assert_eq!(crowded, sections(&sections_cleaned_by(&pairs1, 3)));
+        }
+
+        #[test]
+        fn overlaps_match_section_sets(pairs in prop::collection::vec((section_range(), section_range()), 1..20)) {
+            let assignments = pairs
+                .iter()
+                .map(|((s1, e1), (s2, e2))| format!("{s1}-{e1},{s2}-{e2}\n"))
+                .collect::<String>();
+            let sections = |(s, e): (usize, usize)| (s..=e).collect::<HashSet<_>>();
+            let fully = pairs
+                .iter()
+                .filter(|(l, r)| {
+                    let (l, r) = (sections(*l), sections(*r));
+                    l.is_subset(&r) || r.is_subset(&l)
+                })
+                .count();
+            let partially = pairs
+                .iter()
+                .filter(|(l, r)| !sections(*l).is_disjoint(&sections(*r)))
+                .count();
+            prop_assert_eq!(fully, count_fully_overlapping_ranges(&assignments).unwrap());
+            prop_assert_eq!(partially, count_partially_overlapping_ranges(&assignments).unwrap());
+        }
+    }
 
     #[test]
     fn aoc_example_works() {
@@ -82,7 +353,32 @@ mod tests {
             2-6,4-8
         "};
 
-        assert_eq!(2, count_fully_overlapping_ranges(assignments));
-        assert_eq!(4, count_partially_overlapping_ranges(assignments));
+        assert_eq!(2, count_fully_overlapping_ranges(assignments).unwrap());
+        assert_eq!(4, count_partially_overlapping_ranges(assignments).unwrap());
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        assert!(count_fully_overlapping_ranges("2-4,6-8\n2-3;4-5").is_err());
+        assert!(count_fully_overlapping_ranges("4-2,6-8").is_err());
+    }
+
+    #[test]
+    fn section_sets_work() {
+        let assignments = indoc! {"\
+            2-4,6-8
+            2-3,4-5
+            5-7,7-9
+            2-8,3-7
+            6-6,4-6
+            2-6,4-8
+        "};
+        let pairs = read_pairs(assignments).unwrap();
+        assert_eq!("2-9", sections_cleaned_by(&pairs, 1).to_string());
+        assert_eq!("2-8", sections_cleaned_by(&pairs, 3).to_string());
+        assert_eq!("3-7", sections_cleaned_by(&pairs, 5).to_string());
+        assert!(uncleaned_sections(&pairs).is_empty());
+        let pairs = read_pairs("1-2,8-9\n4-4,4-4\n").unwrap();
+        assert_eq!("3-3,5-7", uncleaned_sections(&pairs).to_string());
     }
 }
@@ -1,78 +1,299 @@
-use eyre::{Context, ContextCompat};
+use crate::parsing::{at, at_line, or_parse_error};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, Context, ContextCompat};
 use std::cmp::max;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
 use std::str::FromStr;
 
-#[derive(Debug)]
-struct Stock {
-    crates: Vec<Vec<char>>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stock {
+    /// labels of the crates of each stack, from the bottom
+    crates: Vec<Vec<String>>,
+}
+
+/// each whitespace separated word of `line`, with its span in characters
+fn words(line: &str) -> Vec<(Range<usize>, &str)> {
+    let mut words = vec![];
+    let mut start = None;
+    for (i, (byte, c)) in line.char_indices().enumerate() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some((i, byte)),
+            (Some((first, first_byte)), true) => {
+                words.push((first..i, &line[first_byte..byte]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((first, first_byte)) = start {
+        words.push((first..line.chars().count(), &line[first_byte..]));
+    }
+    words
 }
 
-//[B] [R] [B] [C] [D] [H] [D] [C] [N]
-//.1...5...9...13
+fn check_label(label: &str) -> eyre::Result<()> {
+    if label.is_empty() || label.contains(|c: char| c.is_whitespace() || c == '[' || c == ']') {
+        bail!("invalid crate label '{label}'");
+    }
+    Ok(())
+}
+
+//    [D]
+//[N] [C]
+//[Z] [M] [P]
+// 1   2   3
+/// crates are put on the stack whose number they are above, whatever their width
 impl FromStr for Stock {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> eyre::Result<Self> {
-        let stock: Vec<Vec<(usize, char)>> = s
+        let lines: Vec<(usize, &str)> = s
             .lines()
-            .rev()
-            .filter(|l| l.contains('['))
-            .map(|l| {
-                let crates: Vec<_> = l
-                    .chars()
-                    .enumerate()
-                    .filter(|(_, c)| c.is_alphabetic())
-                    .map(|(i, c)| ((i - 1) / 4, c))
-                    .collect();
-                crates
-            })
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
             .collect();
-        let stack_nb: usize = 1 + stock
-            .get(0)
-            .and_then(|line| line.last().map(|(i, _)| *i))
-            .unwrap_or(0);
-        let mut crates = vec![vec![]; stack_nb];
-
-        for line in stock {
-            for (i, cr) in line {
-                crates.get_mut(i).unwrap().push(cr);
+        let Some(((footer_idx, footer), rows)) = lines.split_last() else {
+            bail!("no stack numbers");
+        };
+        let numbers = words(footer);
+        for (i, (_, number)) in numbers.iter().enumerate() {
+            if number.parse::<usize>().ok() != Some(i + 1) {
+                bail!(
+                    "{} : expecting stack number {}, not '{number}'",
+                    at_line(*footer_idx),
+                    i + 1
+                );
+            }
+        }
+
+        let mut crates = vec![vec![]; numbers.len()];
+        for (level, (line_idx, line)) in rows.iter().rev().enumerate() {
+            for (span, word) in words(line) {
+                let label = word
+                    .strip_prefix('[')
+                    .and_then(|w| w.strip_suffix(']'))
+                    .with_context(|| {
+                        format!("'{word}' is not a crate at {}", at(*line_idx, span.start))
+                    })?;
+                check_label(label).wrap_err_with(|| at(*line_idx, span.start))?;
+                let stack = numbers
+                    .iter()
+                    .position(|(number, _)| number.start < span.end && span.start < number.end)
+                    .with_context(|| {
+                        format!(
+                            "crate '{word}' is above no stack number at {}",
+                            at(*line_idx, span.start)
+                        )
+                    })?;
+                // a second crate over the same stack in a row, or one floating above a gap
+                if crates[stack].len() != level {
+                    bail!(
+                        "crate '{word}' is not right over stack {} at {}",
+                        stack + 1,
+                        at(*line_idx, span.start)
+                    );
+                }
+                crates[stack].push(label.to_string());
             }
         }
 
         Ok(Self { crates })
     }
 }
+
+/// the drawing of the puzzle input, each column being as wide as its widest label or number
+impl Display for Stock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .crates
+            .iter()
+            .flatten()
+            .map(|label| label.chars().count() + 2)
+            .chain([3, self.crates.len().to_string().len() + 2])
+            .max()
+            .unwrap_or(3);
+        let height = self.crates.iter().map(Vec::len).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .crates
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(label) => format!("{:^width$}", format!("[{label}]")),
+                    None => " ".repeat(width),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" ").trim_end())?;
+        }
+        let footer: Vec<String> = (1..=self.crates.len())
+            .map(|i| format!("{i:^width$}"))
+            .collect();
+        write!(f, "{}", footer.join(" ").trim_end())
+    }
+}
+
 impl Stock {
+    /// a stock of stacks of labelled crates, from the bottom
+    pub fn new(crates: Vec<Vec<String>>) -> eyre::Result<Self> {
+        for label in crates.iter().flatten() {
+            check_label(label)?;
+        }
+        Ok(Self { crates })
+    }
+
+    /// the crate on top of each stack
+    pub fn top_crates(&self) -> String {
+        self.crates
+            .iter()
+            .filter_map(|stack| stack.last())
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// numbers of the stacks which differ in `other`
+    pub fn changed_stacks(&self, other: &Stock) -> Vec<usize> {
+        let len = max(self.crates.len(), other.crates.len());
+        (0..len)
+            .filter(|i| self.crates.get(*i) != other.crates.get(*i))
+            .map(|i| i + 1)
+            .collect()
+    }
+
+    /// applies `mv`, returning the number of lifts it took to `crane`
+    ///
+    /// an invalid move is an error located by its line in [Validation::Strict]
+    pub fn apply(
+        &mut self,
+        mv: &CrateMove,
+        crane: &dyn Crane,
+        validation: Validation,
+    ) -> eyre::Result<usize> {
+        if validation == Validation::Strict {
+            self.validate(mv)
+                .wrap_err_with(|| format!("{} : '{mv}'", at_line(mv.line)))?;
+        }
+        Ok(self.rearange_step(mv.from - 1, mv.to - 1, mv.quantity, crane))
+    }
+
+    fn validate(&self, mv: &CrateMove) -> eyre::Result<()> {
+        for stack in [mv.from, mv.to] {
+            if stack > self.crates.len() {
+                bail!("there is no stack {stack}, only {}", self.crates.len());
+            }
+        }
+        let available = self.crates[mv.from - 1].len();
+        if mv.quantity > available {
+            bail!(
+                "cannot take {} crates from stack {} holding {available}",
+                mv.quantity,
+                mv.from
+            );
+        }
+        Ok(())
+    }
+
+    /// grows the stock up to the stacks of a move, and moves as many crates as there are
     fn rearange_step(
         &mut self,
         source_stack_index: usize,
         target_stack_index: usize,
         quantity: usize,
-        keep_order: bool,
-    ) {
+        crane: &dyn Crane,
+    ) -> usize {
         let len = self.crates.len();
         let max_index = max(source_stack_index, target_stack_index);
         if max_index >= len {
             self.crates.append(&mut vec![vec![]; max_index + 1 - len]);
         }
 
-        let source = self.crates.get_mut(source_stack_index).unwrap();
-        let mut moved_crates: Vec<_> = (0..quantity).filter_map(|_| source.pop()).collect();
-        if keep_order {
-            moved_crates.reverse();
+        let source = &mut self.crates[source_stack_index];
+        let mut moved_crates = source.split_off(source.len().saturating_sub(quantity));
+        let lifts = crane.transfer(&mut moved_crates);
+        self.crates[target_stack_index].append(&mut moved_crates);
+        lifts
+    }
+}
+
+/// a crane model, deciding how a batch of crates is transferred between two stacks
+pub trait Crane {
+    /// reorders `batch`, as it was on its stack (bottom first), into the order it is stacked on
+    /// the target stack, returning the number of lifts it took
+    fn transfer(&self, batch: &mut [String]) -> usize;
+}
+
+/// lifts crates one at a time
+#[derive(Debug, Clone, Copy)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn transfer(&self, batch: &mut [String]) -> usize {
+        batch.reverse();
+        batch.len()
+    }
+}
+
+/// lifts a whole batch at once
+#[derive(Debug, Clone, Copy)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn transfer(&self, batch: &mut [String]) -> usize {
+        usize::from(!batch.is_empty())
+    }
+}
+
+/// lifts batches in chunks of at most `max` crates, from the top
+#[derive(Debug, Clone, Copy)]
+pub struct LiftCapacity {
+    max: usize,
+}
+
+impl LiftCapacity {
+    pub fn new(max: usize) -> eyre::Result<Self> {
+        if max == 0 {
+            bail!("a crane must lift at least one crate");
+        }
+        Ok(Self { max })
+    }
+}
+
+impl Crane for LiftCapacity {
+    fn transfer(&self, batch: &mut [String]) -> usize {
+        // the top chunk is lifted first, each chunk keeping its order
+        batch.reverse();
+        for chunk in batch.chunks_mut(self.max) {
+            chunk.reverse();
         }
-        self.crates
-            .get_mut(target_stack_index)
-            .unwrap()
-            .append(&mut moved_crates);
+        batch.len().div_ceil(self.max)
     }
 }
 
+/// how moves which do not fit the stock are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validation {
+    /// missing stacks are added, and a stack running dry ends the move
+    Lenient,
+    /// such moves are errors
+    Strict,
+}
+
 #[derive(Debug)]
-struct CrateMove {
+pub struct CrateMove {
     quantity: usize,
     from: usize,
     to: usize,
+    /// 0-based line index in the input, when read from one
+    line: usize,
+}
+
+impl Display for CrateMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.quantity, self.from, self.to
+        )
+    }
 }
 
 impl FromStr for CrateMove {
@@ -80,73 +301,334 @@ impl FromStr for CrateMove {
 
     fn from_str(s: &str) -> eyre::Result<Self> {
         let mut command = s.trim().split(' ');
-        // partial parse
-        command.next();
-        let quantity = command
-            .next()
-            .context("reading move")
-            .and_then(|q| q.parse().context("parsing quantity"))?;
-        command.next();
-        let from = command
-            .next()
-            .context("reading from")
-            .and_then(|q| q.parse().context("parsing from"))?;
-        command.next();
-        let to = command
-            .next()
-            .context("reading to")
-            .and_then(|q| q.parse().context("parsing to"))?;
-
-        Ok(Self { quantity, from, to })
-    }
-}
-
-fn arrange_stock(stock_and_moves: &str, keep_order: bool) -> String {
-    let mut stock: Stock = stock_and_moves.parse().expect("could not parse stock");
-    let moves: Vec<CrateMove> = stock_and_moves
+        let mut read_number = |keyword: &str| -> eyre::Result<usize> {
+            match command.next() {
+                Some(k) if k == keyword => {}
+                _ => bail!("expecting '{keyword}'"),
+            }
+            command
+                .next()
+                .with_context(|| format!("reading {keyword}"))
+                .and_then(|q| q.parse().with_context(|| format!("parsing {keyword}")))
+        };
+        let quantity = read_number("move")?;
+        let from = read_number("from")?;
+        let to = read_number("to")?;
+        if from == 0 || to == 0 {
+            bail!("stacks are numbered from 1");
+        }
+
+        Ok(Self {
+            quantity,
+            from,
+            to,
+            line: 0,
+        })
+    }
+}
+
+/// the stock drawing, and each move parsed with its line number
+#[allow(clippy::type_complexity)]
+fn split_stock_and_moves(
+    stock_and_moves: &str,
+) -> eyre::Result<(String, impl Iterator<Item = eyre::Result<CrateMove>> + '_)> {
+    let blank = stock_and_moves
         .lines()
-        .filter(|l| l.starts_with("move "))
-        .map(|l| l.parse().unwrap())
-        .collect();
+        .position(|l| l.trim().is_empty())
+        .context("no blank line between the stock and the moves")?;
+    let stock = stock_and_moves
+        .lines()
+        .take(blank)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let moves = stock_and_moves
+        .lines()
+        .enumerate()
+        .skip(blank + 1)
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(move |(line, l)| {
+            l.parse::<CrateMove>()
+                .map(|mv| CrateMove { line, ..mv })
+                .wrap_err_with(|| format!("{} : '{l}'", at_line(line)))
+        });
+    Ok((stock, moves))
+}
+
+pub fn read_stock_and_moves(stock_and_moves: &str) -> eyre::Result<(Stock, Vec<CrateMove>)> {
+    let (stock, moves) = split_stock_and_moves(stock_and_moves)?;
+    let stock = stock.parse().wrap_err("parsing stock")?;
+    Ok((stock, moves.collect::<eyre::Result<_>>()?))
+}
+
+/// the stock after each move
+pub fn replay<'m>(
+    mut stock: Stock,
+    moves: &'m [CrateMove],
+    crane: &'m dyn Crane,
+    validation: Validation,
+) -> impl Iterator<Item = eyre::Result<Stock>> + 'm {
+    moves.iter().map(move |mv| {
+        stock.apply(mv, crane, validation)?;
+        Ok(stock.clone())
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arrangement {
+    pub top_crates: String,
+    /// total number of individual lifts made by the crane
+    pub lifts: usize,
+}
 
+fn rearrange(
+    mut stock: Stock,
+    moves: &[CrateMove],
+    crane: &dyn Crane,
+    validation: Validation,
+) -> eyre::Result<Arrangement> {
+    let mut lifts = 0;
     for mv in moves {
-        stock.rearange_step(mv.from - 1, mv.to - 1, mv.quantity, keep_order);
+        lifts += stock.apply(mv, crane, validation)?;
     }
+    Ok(Arrangement {
+        top_crates: stock.top_crates(),
+        lifts,
+    })
+}
 
-    stock
-        .crates
-        .iter()
-        .filter_map(|stack| stack.iter().rev().next())
-        .collect()
+pub fn arrange_stock(
+    stock_and_moves: &str,
+    crane: &dyn Crane,
+    validation: Validation,
+) -> eyre::Result<Arrangement> {
+    let (stock, moves) = read_stock_and_moves(stock_and_moves)?;
+    rearrange(stock, &moves, crane, validation)
+}
+
+pub struct SupplyStacks {
+    stock: Stock,
+    moves: Vec<CrateMove>,
 }
 
-pub fn supply_stack() {
-    let stock_and_moves = include_str!("../resources/day5_stocks_and_moves.txt");
-    let tops_9000 = arrange_stock(stock_and_moves, false);
-    println!("top crates 9000 : {tops_9000}");
-    let tops_9001 = arrange_stock(stock_and_moves, true);
-    println!("top crates 9001 : {tops_9001}");
+impl Solution<'_> for SupplyStacks {
+    fn parse(stock_and_moves: &str) -> eyre::Result<Self> {
+        let (stock, moves) = read_stock_and_moves(stock_and_moves)?;
+        Ok(Self { stock, moves })
+    }
+    /// CrateMover 9000
+    fn part1(&self) -> eyre::Result<Answer> {
+        let arrangement = rearrange(
+            self.stock.clone(),
+            &self.moves,
+            &CrateMover9000,
+            Validation::Strict,
+        )?;
+        Ok(arrangement.top_crates.into())
+    }
+    /// CrateMover 9001
+    fn part2(&self) -> eyre::Result<Answer> {
+        let arrangement = rearrange(
+            self.stock.clone(),
+            &self.moves,
+            &CrateMover9001,
+            Validation::Strict,
+        )?;
+        Ok(arrangement.top_crates.into())
+    }
+    /// stack heights do not depend on the crane, a first invalid move is found by either
+    fn check(stock_and_moves: &str) -> Vec<eyre::Report> {
+        let problems = match split_stock_and_moves(stock_and_moves) {
+            Ok((stock, moves)) => stock
+                .parse::<Stock>()
+                .wrap_err("parsing stock")
+                .err()
+                .into_iter()
+                .chain(moves.filter_map(Result::err))
+                .collect(),
+            Err(e) => vec![e],
+        };
+        or_parse_error(problems, || {
+            arrange_stock(stock_and_moves, &CrateMover9000, Validation::Strict)
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
+
+    const STOCK_AND_MOVES: &str = indoc! {"
+        [D]
+    [N] [C]
+    [Z] [M] [P]
+     1   2   3
+
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2
+    "};
 
     #[test]
     fn aoc_example_works() {
-        let stock_and_moves = indoc! {"
-            [D]
-        [N] [C]
-        [Z] [M] [P]
-         1   2   3
-
-        move 1 from 2 to 1
-        move 3 from 1 to 3
-        move 2 from 2 to 1
-        move 1 from 1 to 2
-        "};
-        assert_eq!("CMZ", arrange_stock(stock_and_moves, false));
-        assert_eq!("MCD", arrange_stock(stock_and_moves, true));
+        let top_crates = |crane: &dyn Crane| {
+            arrange_stock(STOCK_AND_MOVES, crane, Validation::Strict)
+                .unwrap()
+                .top_crates
+        };
+        assert_eq!("CMZ", top_crates(&CrateMover9000));
+        assert_eq!("MCD", top_crates(&CrateMover9001));
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = arrange_stock(
+            "[A]\n 1\n\nmove 1 from 1 to 2\nmove one from 1 to 2",
+            &CrateMover9000,
+            Validation::Lenient,
+        )
+        .unwrap_err();
+        assert_eq!("line 5 : 'move one from 1 to 2'", error.to_string());
+
+        let problems: Vec<String> =
+            SupplyStacks::check("[A]\n 1\n\nmove one from 1 to 2\nmove 1 from 0 to 1")
+                .iter()
+                .map(|e| e.to_string())
+                .collect();
+        assert_eq!(
+            vec![
+                "line 4 : 'move one from 1 to 2'",
+                "line 5 : 'move 1 from 0 to 1'"
+            ],
+            problems
+        );
+    }
+
+    #[test]
+    fn crane_lifts_work() {
+        let arrangement = |crane: &dyn Crane| {
+            let arrangement = arrange_stock(STOCK_AND_MOVES, crane, Validation::Strict).unwrap();
+            (arrangement.top_crates, arrangement.lifts)
+        };
+        assert_eq!(("CMZ".to_string(), 7), arrangement(&CrateMover9000));
+        assert_eq!(("MCD".to_string(), 4), arrangement(&CrateMover9001));
+        let capacity = LiftCapacity::new(2).unwrap();
+        assert_eq!(("MCZ".to_string(), 5), arrangement(&capacity));
+        assert_eq!(
+            arrangement(&CrateMover9000),
+            arrangement(&LiftCapacity::new(1).unwrap())
+        );
+        assert!(LiftCapacity::new(0).is_err());
+    }
+
+    #[test]
+    fn strict_validation_works() {
+        let invalid = "[A]\n[B] [C]\n 1   2\n\nmove 1 from 2 to 3\n\nmove 3 from 1 to 2";
+        assert_eq!(
+            "BC",
+            arrange_stock(invalid, &CrateMover9000, Validation::Lenient)
+                .unwrap()
+                .top_crates
+        );
+        let error = arrange_stock(invalid, &CrateMover9000, Validation::Strict).unwrap_err();
+        assert_eq!(
+            "line 5 : 'move 1 from 2 to 3': there is no stack 3, only 2",
+            format!("{error:#}")
+        );
+        let invalid = "[A]\n[B] [C]\n 1   2\n\nmove 1 from 2 to 1\n\nmove 4 from 1 to 2";
+        let error = arrange_stock(invalid, &CrateMover9000, Validation::Strict).unwrap_err();
+        assert_eq!(
+            "line 7 : 'move 4 from 1 to 2': cannot take 4 crates from stack 1 holding 3",
+            format!("{error:#}")
+        );
+        assert_eq!(
+            "B",
+            arrange_stock(invalid, &CrateMover9000, Validation::Lenient)
+                .unwrap()
+                .top_crates
+        );
+        assert_eq!(1, SupplyStacks::check(invalid).len());
+    }
+
+    #[test]
+    fn stock_replay_works() {
+        let (stock, moves) = read_stock_and_moves(STOCK_AND_MOVES).unwrap();
+        assert_eq!(
+            STOCK_AND_MOVES.split_once("\n\n").unwrap().0,
+            stock.to_string()
+        );
+        let states: Vec<Stock> = replay(stock.clone(), &moves, &CrateMover9000, Validation::Strict)
+            .collect::<eyre::Result<_>>()
+            .unwrap();
+        assert_eq!(4, states.len());
+        assert_eq!(vec![1, 2], stock.changed_stacks(&states[0]));
+        assert_eq!(
+            indoc! {"
+                        [Z]
+                        [N]
+                [M]     [D]
+                [C]     [P]
+                 1   2   3"},
+            states[2].to_string()
+        );
+        assert_eq!("CMZ", states[3].top_crates());
+        assert_eq!("move 1 from 2 to 1", moves[0].to_string());
+    }
+
+    #[test]
+    fn crlf_input_works() {
+        let stock_and_moves = "    [D]\r\n[N] [C]\r\n[Z] [M] [P]\r\n 1   2   3\r\n\r\nmove 1 from 2 to 1\r\nmove 3 from 1 to 3\r\nmove 2 from 2 to 1\r\nmove 1 from 1 to 2\r\n";
+        let stacks = SupplyStacks::parse(stock_and_moves).unwrap();
+        assert_eq!(Answer::Text("CMZ".to_string()), stacks.part1().unwrap());
+        assert_eq!(Answer::Text("MCD".to_string()), stacks.part2().unwrap());
+        assert!(SupplyStacks::check(stock_and_moves).is_empty());
+    }
+
+    #[test]
+    fn stock_format_works() {
+        let stock = Stock::new(vec![
+            vec!["A".to_string(), "BCD".to_string()],
+            vec!["E".to_string()],
+        ])
+        .unwrap();
+        let drawing = indoc! {"
+            [BCD]
+             [A]   [E]
+              1     2"};
+        assert_eq!(drawing, stock.to_string());
+        assert_eq!(stock, drawing.parse().unwrap());
+        assert_eq!("BCDE", stock.top_crates());
+        assert!(Stock::new(vec![vec!["A B".to_string()]]).is_err());
+
+        let errors: Vec<String> = [
+            "[A] [B]\n 1",
+            "[A] [B]\n    [C]\n 1   2",
+            "[A] [B]\n 1   3",
+            "[A\n 1",
+            "",
+        ]
+        .iter()
+        .map(|drawing| format!("{:#}", drawing.parse::<Stock>().unwrap_err()))
+        .collect();
+        assert_eq!(
+            vec![
+                "crate '[B]' is above no stack number at line 1, column 5",
+                "crate '[A]' is not right over stack 1 at line 1, column 1",
+                "line 2 : expecting stack number 2, not '3'",
+                "'[A' is not a crate at line 1, column 1",
+                "no stack numbers",
+            ],
+            errors
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn stock_drawings_round_trip(crates in prop::collection::vec(prop::collection::vec("[A-Z0-9]{1,3}", 0..5), 1..13)) {
+            let stock = Stock::new(crates).unwrap();
+            prop_assert_eq!(&stock, &stock.to_string().parse::<Stock>().unwrap());
+        }
     }
 }
@@ -1,43 +1,230 @@
-fn detect_marker_end_pos(signal: &[u8], marker_len: usize) -> usize {
-    let len = signal.len();
-    if len < marker_len {
-        panic!("cannot detect a {marker_len} header in a {len} signal !");
-    }
-    'main: for i in 0..(len - marker_len) {
-        let chars = &signal[i..i + marker_len];
-        for j in 0..(marker_len - 1) {
-            let r = chars[j];
-            for c in &chars[j + 1..marker_len] {
-                if r == *c {
-                    continue 'main;
-                }
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre};
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::io::{BufReader, Read};
+
+/// what makes a marker : a window of `len` characters holding at least `min_distinct` distinct
+/// ones, optionally ignoring their case and only made of the characters of an alphabet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkerSpec {
+    len: usize,
+    min_distinct: usize,
+    case_insensitive: bool,
+    alphabet: Option<Vec<u8>>,
+}
+
+impl MarkerSpec {
+    /// `len` characters all distinct, as in the puzzle
+    pub fn distinct(len: usize) -> Self {
+        Self {
+            len,
+            min_distinct: len,
+            case_insensitive: false,
+            alphabet: None,
+        }
+    }
+
+    pub fn with_min_distinct(self, min_distinct: usize) -> Self {
+        Self {
+            min_distinct,
+            ..self
+        }
+    }
+
+    /// 'a' and 'A' are the same character
+    pub fn case_insensitive(self) -> Self {
+        Self {
+            case_insensitive: true,
+            ..self
+        }
+    }
+
+    /// a marker cannot overlap a character not in `alphabet`
+    pub fn with_alphabet(self, alphabet: &[u8]) -> Self {
+        Self {
+            alphabet: Some(alphabet.to_vec()),
+            ..self
+        }
+    }
+
+    fn normalize(&self, byte: u8) -> u8 {
+        if self.case_insensitive {
+            byte.to_ascii_lowercase()
+        } else {
+            byte
+        }
+    }
+}
+
+impl Display for MarkerSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} distinct characters", self.min_distinct)?;
+        if self.min_distinct < self.len {
+            write!(f, " out of {}", self.len)?;
+        }
+        if self.case_insensitive {
+            write!(f, ", case insensitive")?;
+        }
+        if let Some(alphabet) = &self.alphabet {
+            write!(f, ", in '{}'", alphabet.escape_ascii())?;
+        }
+        Ok(())
+    }
+}
+
+/// sliding window over a signal, knowing in O(1) whether its last bytes make a marker
+#[derive(Debug, Clone)]
+pub struct MarkerDetector {
+    spec: MarkerSpec,
+    allowed: [bool; 256],
+    window: VecDeque<u8>,
+    counts: [usize; 256],
+    distinct: usize,
+    /// characters of the window not in the alphabet
+    foreign: usize,
+    position: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(spec: MarkerSpec) -> eyre::Result<Self> {
+        if spec.len == 0 {
+            bail!("a marker has at least one character");
+        }
+        if !(1..=spec.len).contains(&spec.min_distinct) {
+            bail!(
+                "a {} characters marker cannot have {} distinct ones",
+                spec.len,
+                spec.min_distinct
+            );
+        }
+        let mut allowed = [spec.alphabet.is_none(); 256];
+        for byte in spec.alphabet.iter().flatten() {
+            allowed[spec.normalize(*byte) as usize] = true;
+        }
+        Ok(Self {
+            window: VecDeque::with_capacity(spec.len + 1),
+            spec,
+            allowed,
+            counts: [0; 256],
+            distinct: 0,
+            foreign: 0,
+            position: 0,
+        })
+    }
+
+    /// reads the next byte, returning the position after it when it ends a marker
+    ///
+    /// `\n` and `\r` are skipped, a captured signal being split in lines :
+    /// they only count in the position
+    pub fn push(&mut self, byte: u8) -> Option<usize> {
+        self.position += 1;
+        if matches!(byte, b'\n' | b'\r') {
+            return None;
+        }
+        let byte = self.spec.normalize(byte);
+        self.window.push_back(byte);
+        self.count(byte, true);
+        if self.window.len() > self.spec.len {
+            let old = self.window.pop_front().expect("the window is not empty");
+            self.count(old, false);
+        }
+        (self.window.len() == self.spec.len
+            && self.foreign == 0
+            && self.distinct >= self.spec.min_distinct)
+            .then_some(self.position)
+    }
+
+    fn count(&mut self, byte: u8, entering: bool) {
+        if !self.allowed[byte as usize] {
+            if entering {
+                self.foreign += 1;
+            } else {
+                self.foreign -= 1;
+            }
+            return;
+        }
+        let count = &mut self.counts[byte as usize];
+        if entering {
+            *count += 1;
+            if *count == 1 {
+                self.distinct += 1;
+            }
+        } else {
+            *count -= 1;
+            if *count == 0 {
+                self.distinct -= 1;
             }
         }
-        return i + marker_len;
     }
-    0
 }
 
-fn detect_packet_marker(signal: &str) -> usize {
+/// end position of every marker of `signal`, overlapping ones included
+pub fn markers(
+    signal: impl IntoIterator<Item = u8>,
+    spec: &MarkerSpec,
+) -> eyre::Result<impl Iterator<Item = usize>> {
+    let mut detector = MarkerDetector::new(spec.clone())?;
+    Ok(signal.into_iter().filter_map(move |b| detector.push(b)))
+}
+
+/// [markers] of a signal too large to be loaded at once
+pub fn read_markers(
+    signal: impl Read,
+    spec: &MarkerSpec,
+) -> eyre::Result<impl Iterator<Item = eyre::Result<usize>>> {
+    let mut detector = MarkerDetector::new(spec.clone())?;
+    Ok(BufReader::new(signal).bytes().filter_map(move |b| match b {
+        Ok(b) => detector.push(b).map(Ok),
+        Err(e) => Some(Err(e.into())),
+    }))
+}
+
+/// end position of the first marker of `signal`
+pub fn detect_marker(signal: &[u8], spec: &MarkerSpec) -> eyre::Result<usize> {
+    markers(signal.iter().copied(), spec)?
+        .next()
+        .ok_or_else(|| eyre!("no marker of {spec} in signal"))
+}
+
+pub fn detect_marker_end_pos(signal: &[u8], marker_len: usize) -> eyre::Result<usize> {
+    let len = signal.len();
+    if len < marker_len {
+        bail!("cannot detect a {marker_len} header in a {len} signal !");
+    }
+    detect_marker(signal, &MarkerSpec::distinct(marker_len))
+}
+
+pub fn detect_packet_marker(signal: &str) -> eyre::Result<usize> {
     detect_marker_end_pos(signal.as_bytes(), 4)
 }
-fn detect_message_marker(signal: &str) -> usize {
+pub fn detect_message_marker(signal: &str) -> eyre::Result<usize> {
     detect_marker_end_pos(signal.as_bytes(), 14)
 }
 
-pub fn detect_signal() {
-    let signal = include_str!("../resources/day6_signal.txt");
-
-    let packet_pos = detect_packet_marker(signal);
-    println!("packet detected at {packet_pos}");
+pub struct Signal<'i> {
+    signal: &'i str,
+}
 
-    let message_pos = detect_message_marker(signal);
-    println!("message detected at {message_pos}")
+impl<'i> Solution<'i> for Signal<'i> {
+    fn parse(signal: &'i str) -> eyre::Result<Self> {
+        Ok(Self {
+            signal: signal.trim_end(),
+        })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(detect_packet_marker(self.signal)?.into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(detect_message_marker(self.signal)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
 
     #[test]
     fn aoc_examples_works() {
@@ -49,8 +236,98 @@ mod tests {
             ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
         ];
         for (signal, pos_header, pos_message) in test_cases {
-            assert_eq!(pos_header, detect_packet_marker(signal));
-            assert_eq!(pos_message, detect_message_marker(signal));
+            assert_eq!(pos_header, detect_packet_marker(signal).unwrap());
+            assert_eq!(pos_message, detect_message_marker(signal).unwrap());
+        }
+        assert_eq!(4, detect_packet_marker("abcd").unwrap());
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        assert!(detect_packet_marker("abcabc").is_err());
+        assert!(detect_message_marker("abcd").is_err());
+    }
+
+    #[test]
+    fn all_markers_are_streamed() {
+        assert!(detect_marker_end_pos(b"abcd", 0).is_err());
+
+        let spec = MarkerSpec::distinct(4);
+        let all: Vec<usize> = markers(*b"abcdabbcda", &spec).unwrap().collect();
+        assert_eq!(vec![4, 5, 6, 10], all);
+        let read: Vec<usize> = read_markers(&b"abcdabbcda"[..], &spec)
+            .unwrap()
+            .collect::<eyre::Result<_>>()
+            .unwrap();
+        assert_eq!(all, read);
+    }
+
+    #[test]
+    fn marker_specs_work() {
+        let all = |signal: &[u8], spec: &MarkerSpec| -> Vec<usize> {
+            markers(signal.iter().copied(), spec).unwrap().collect()
+        };
+        let spec = MarkerSpec::distinct(4).with_min_distinct(3);
+        assert_eq!(vec![4, 5, 6, 7, 8, 9, 10], all(b"abcdabbcda", &spec));
+        assert_eq!(vec![4, 7], all(b"abcAbcab", &MarkerSpec::distinct(4)));
+        assert!(all(b"abcAbcab", &MarkerSpec::distinct(4).case_insensitive()).is_empty());
+
+        let spec = MarkerSpec::distinct(3).with_alphabet(b"abc");
+        assert_eq!(vec![3, 8, 9], all(b"abcd\nbcab", &spec));
+        let spec = MarkerSpec::distinct(3)
+            .with_alphabet(b"ABC")
+            .case_insensitive();
+        assert_eq!(vec![3], all(b"aBcd", &spec));
+        assert_eq!(
+            "2 distinct characters out of 3, case insensitive, in 'ABC'",
+            spec.with_min_distinct(2).to_string()
+        );
+
+        assert!(MarkerDetector::new(MarkerSpec::distinct(3).with_min_distinct(4)).is_err());
+        assert!(MarkerDetector::new(MarkerSpec::distinct(3).with_min_distinct(0)).is_err());
+        assert_eq!(
+            "no marker of 4 distinct characters in signal",
+            detect_marker(b"abcabc", &MarkerSpec::distinct(4))
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn line_terminators_are_skipped() {
+        let spec = MarkerSpec::distinct(4);
+        let all = |signal: &[u8]| -> Vec<usize> {
+            read_markers(signal, &spec)
+                .unwrap()
+                .collect::<eyre::Result<_>>()
+                .unwrap()
+        };
+        assert!(all(b"abcabc\n").is_empty());
+        assert!(all(b"abcabc\r\n").is_empty());
+        assert_eq!(vec![5, 6], all(b"ab\ncda\r\n"));
+    }
+
+    proptest! {
+        #[test]
+        fn markers_match_their_windows(signal in "[a-e\n]{0,60}", len in 1..5usize) {
+            let spec = MarkerSpec::distinct(len);
+            let characters: Vec<(usize, u8)> = signal
+                .bytes()
+                .enumerate()
+                .filter(|(_, b)| *b != b'\n')
+                .collect();
+            let expected: Vec<usize> = characters
+                .windows(len)
+                .filter(|w| w.iter().map(|(_, b)| b).collect::<HashSet<_>>().len() == len)
+                .map(|w| w[len - 1].0 + 1)
+                .collect();
+            let all: Vec<usize> = markers(signal.bytes(), &spec).unwrap().collect();
+            prop_assert_eq!(&expected, &all);
+            let read: Vec<usize> = read_markers(signal.as_bytes(), &spec)
+                .unwrap()
+                .collect::<eyre::Result<_>>()
+                .unwrap();
+            prop_assert_eq!(all, read);
         }
     }
 }
@@ -1,217 +1,812 @@
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
 
-use eyre::{Context, ContextCompat};
+use crate::parsing::{check_lines, parse_lines};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, Context, ContextCompat};
 
 #[derive(Debug)]
-struct Dir {
-    direct_files_size: usize,
+struct Dir<'a> {
+    name: &'a str,
+    parent: Option<usize>,
+    /// index of each subdirectory, by name
+    dirs: BTreeMap<&'a str, usize>,
+    /// size of each file, by name
+    files: BTreeMap<&'a str, usize>,
+    /// whether an `ls` of it was read
+    listed: bool,
 }
+
+impl<'a> Dir<'a> {
+    fn new(name: &'a str, parent: Option<usize>) -> Self {
+        Self {
+            name,
+            parent,
+            dirs: BTreeMap::new(),
+            files: BTreeMap::new(),
+            listed: false,
+        }
+    }
+}
+
 #[derive(Debug)]
-struct File {
-    //    name : &'static [u8],
+struct File<'a> {
+    name: &'a str,
     size: usize,
 }
 
-struct FileSystem {
-    dirs: HashMap<Vec<&'static [u8]>, Dir>, // TODO : use a Trie<&'static [u8], Dir>
-    current_dir: Vec<&'static [u8]>,
+const ROOT: usize = 0;
+
+/// directories summed by the first part are at most this large
+pub const SMALL_DIR_SIZE: usize = 100000;
+pub const DISK_CAPACITY: usize = 70000000;
+/// free space needed by the update of the second part
+pub const UPDATE_SIZE: usize = 30000000;
+
+/// how a file system is rendered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Listing {
+    /// largest entries first, instead of by name
+    pub by_size: bool,
+    /// entries deeper than this are left out, the root being at depth 0
+    pub max_depth: Option<usize>,
 }
-enum Line {
-    Cd(&'static [u8]),
+
+impl Listing {
+    fn shows(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max| depth <= max)
+    }
+}
+
+/// `name` in the directory at `parent`
+fn child_path(parent: &str, name: &str) -> String {
+    match parent {
+        "/" => format!("/{name}"),
+        _ => format!("{parent}/{name}"),
+    }
+}
+
+/// the `ls` whose output is being read
+#[derive(Debug, Clone)]
+struct Ls<'a> {
+    dir: usize,
+    /// the directory was already listed before
+    again: bool,
+    /// entries of the directory before the `ls`
+    previous: usize,
+    /// entries of the output which were already known
+    known: usize,
+    /// names of the output so far
+    names: BTreeSet<&'a str>,
+}
+
+pub struct FileSystem<'a> {
+    /// every directory known from the transcript, a parent being before its children
+    dirs: Vec<Dir<'a>>,
+    current_dir: usize,
+    ls: Option<Ls<'a>>,
+}
+
+enum Line<'a> {
+    Cd(&'a str),
     Ls,
-    D(&'static [u8]),
-    F(File),
+    D(&'a str),
+    F(File<'a>),
 }
 
-impl Line {
-    fn try_from_str(s: &'static str) -> eyre::Result<Self> {
-        if s.starts_with("$ cd ") {
-            return Ok(Self::Cd(&s.trim().as_bytes()[5..]));
+impl<'a> Line<'a> {
+    fn try_from_str(s: &'a str) -> eyre::Result<Self> {
+        if let Some(target) = s.strip_prefix("$ cd ") {
+            let target = target.trim();
+            if target.is_empty() {
+                bail!("no directory to change to");
+            }
+            return Ok(Self::Cd(target));
         }
-        if s.starts_with("$ ls") {
+        if let Some(arguments) = s.strip_prefix("$ ls") {
+            if !arguments.trim().is_empty() {
+                bail!("ls takes no arguments");
+            }
             return Ok(Self::Ls);
         }
+        if let Some(command) = s.strip_prefix("$ ") {
+            bail!("unknown command '{}'", command.trim());
+        }
 
-        if s.starts_with("dir ") {
-            return Ok(Self::D(&s.trim().as_bytes()[4..]));
+        if let Some(name) = s.strip_prefix("dir ") {
+            return Ok(Self::D(name.trim()));
         }
 
-        let size: usize = s
-            .split(' ')
-            .next()
-            .context("reading size")?
-            .parse()
-            .context("parsing size")?;
-        Ok(Self::F(File { size }))
+        let (size, name) = s.split_once(' ').context("reading size and name")?;
+        let size: usize = size.parse().context("parsing size")?;
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("no file name");
+        }
+        Ok(Self::F(File { name, size }))
     }
 }
 
-impl FileSystem {
-    fn new() -> Self {
+impl Default for FileSystem<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> FileSystem<'a> {
+    pub fn new() -> Self {
         Self {
-            dirs: HashMap::new(),
-            current_dir: vec![],
+            dirs: vec![Dir::new("/", None)],
+            current_dir: ROOT,
+            ls: None,
         }
     }
-    fn change_dir(&mut self, target: &'static [u8]) {
-        match target {
-            t if t == "..".as_bytes() => {
-                self.current_dir.pop();
-            }
-            t if t == "/".as_bytes() => {
-                self.current_dir.clear();
-            }
-            t => {
-                for subdir in t.split(|c| *c == b'/') {
-                    self.current_dir.push(subdir);
+
+    /// `name` in `parent`, added when it was not known yet
+    fn subdir(&mut self, parent: usize, name: &'a str) -> usize {
+        if let Some(dir) = self.dirs[parent].dirs.get(name) {
+            return *dir;
+        }
+        let dir = self.dirs.len();
+        self.dirs.push(Dir::new(name, Some(parent)));
+        self.dirs[parent].dirs.insert(name, dir);
+        dir
+    }
+
+    /// `..` above the root stays on it, as `cd` does, and unknown directories are added
+    ///
+    /// fails on the first of them, once the whole target was reached
+    fn change_dir(&mut self, target: &'a str) -> eyre::Result<()> {
+        let mut problem = Ok(());
+        if target.starts_with('/') {
+            self.current_dir = ROOT;
+        }
+        for name in target.split('/').filter(|n| !n.is_empty()) {
+            let dir = &self.dirs[self.current_dir];
+            if problem.is_ok() {
+                if name == ".." && dir.parent.is_none() {
+                    problem = Err(eyre!("cd .. above the root"));
+                } else if name != ".." && name != "." && !dir.dirs.contains_key(name) {
+                    let path = child_path(&self.path(self.current_dir), name);
+                    problem = Err(eyre!("cd into '{path}' which was never listed"));
                 }
             }
+            self.current_dir = match name {
+                ".." => self.dirs[self.current_dir].parent.unwrap_or(ROOT),
+                "." => self.current_dir,
+                _ => self.subdir(self.current_dir, name),
+            };
         }
+        problem
     }
-    fn add_file(&mut self, file: File) {
-        let dir = self.dirs.get_mut(&self.current_dir);
-        if let Some(d) = dir {
-            d.direct_files_size += file.size;
-        } else {
-            self.dirs.insert(
-                self.current_dir.clone(),
-                Dir {
-                    direct_files_size: file.size,
-                },
+
+    fn add_file(&mut self, file: File<'a>) {
+        self.dirs[self.current_dir]
+            .files
+            .insert(file.name, file.size);
+    }
+
+    /// whether an `ls` output entry agrees with what is known of its directory
+    fn check_entry(&mut self, name: &'a str, size: Option<usize>) -> eyre::Result<()> {
+        let Some(listed_dir) = self.ls.as_ref().map(|ls| ls.dir) else {
+            bail!("output without a preceding ls");
+        };
+        let path = self.path(listed_dir);
+        let ls = self.ls.as_mut().expect("an ls is being read");
+        let dir = &self.dirs[listed_dir];
+        if !ls.names.insert(name) {
+            bail!("'{name}' is listed twice by the same ls of '{path}'");
+        }
+        let known = match size {
+            Some(_) => dir.files.contains_key(name),
+            None => dir.dirs.contains_key(name),
+        };
+        if known {
+            ls.known += 1;
+        }
+        let again = ls.again;
+        if let (Some(size), Some(listed)) = (size, dir.files.get(name)) {
+            if size != *listed {
+                bail!("file '{name}' of '{path}' was listed with size {listed}, not {size}");
+            }
+        }
+        if known {
+            return Ok(());
+        }
+        if dir.files.contains_key(name) || dir.dirs.contains_key(name) {
+            bail!("'{name}' of '{path}' is both a file and a directory");
+        }
+        if again {
+            bail!("'{name}' was not in the previous listing of '{path}'");
+        }
+        Ok(())
+    }
+
+    /// marks the directory of the `ls` being read as listed, failing when a second listing
+    /// of it misses some entries
+    fn end_ls(&mut self) -> eyre::Result<()> {
+        let Some(ls) = self.ls.take() else {
+            return Ok(());
+        };
+        self.dirs[ls.dir].listed = true;
+        if ls.again && ls.known < ls.previous {
+            bail!(
+                "the listing of '{}' misses {} entries of the previous one",
+                self.path(ls.dir),
+                ls.previous - ls.known
             );
         }
+        Ok(())
     }
 
-    fn parse_line(&mut self, raw_line: &'static str) -> eyre::Result<()> {
+    /// applies `raw_line`, whatever its consistency with the lines before
+    pub fn parse_line(&mut self, raw_line: &'a str) -> eyre::Result<()> {
+        self.read_line(raw_line, false)
+    }
+
+    /// [Self::parse_line], failing on a line inconsistent with the ones before
+    ///
+    /// the line is applied all the same, so that the next ones can still be checked
+    pub fn check_line(&mut self, raw_line: &'a str) -> eyre::Result<()> {
+        self.read_line(raw_line, true)
+    }
+
+    fn read_line(&mut self, raw_line: &'a str, strict: bool) -> eyre::Result<()> {
         let line: Line = Line::try_from_str(raw_line)?;
-        match line {
+        let problem = match line {
             Line::Cd(cd) => {
-                self.change_dir(cd);
+                let ended = self.end_ls();
+                ended.and(self.change_dir(cd))
+            }
+            Line::Ls => {
+                let ended = self.end_ls();
+                let dir = &self.dirs[self.current_dir];
+                self.ls = Some(Ls {
+                    dir: self.current_dir,
+                    again: dir.listed,
+                    previous: dir.dirs.len() + dir.files.len(),
+                    known: 0,
+                    names: BTreeSet::new(),
+                });
+                ended
+            }
+            Line::D(name) => {
+                let checked = self.check_entry(name, None);
+                self.subdir(self.current_dir, name);
+                checked
+            }
+            Line::F(f) => {
+                let checked = self.check_entry(f.name, Some(f.size));
+                self.add_file(f);
+                checked
             }
-            Line::Ls => {}
-            Line::D(_) => {}
-            Line::F(f) => self.add_file(f),
+        };
+        if strict {
+            problem
+        } else {
+            Ok(())
         }
-        Ok(())
     }
 
-    fn get_total_size(&self, dir: &Vec<&[u8]>) -> usize {
-        self.dirs
+    /// the directory at an absolute `path` such as `/a/e`
+    fn find(&self, path: &str) -> Option<usize> {
+        path.split('/')
+            .filter(|n| !n.is_empty())
+            .try_fold(ROOT, |dir, name| self.dirs[dir].dirs.get(name).copied())
+    }
+
+    /// total size of the files under each directory, in a single pass from the deepest ones
+    fn total_sizes(&self) -> eyre::Result<Vec<usize>> {
+        let overflow = |dir| eyre!("the total size of {} overflows", self.path(dir));
+        let mut sizes: Vec<usize> = self
+            .dirs
             .iter()
-            .filter(|(path, _)| {
-                path.len() >= dir.len() && path.iter().zip(dir.iter()).all(|(p, d)| **p == **d)
+            .enumerate()
+            .map(|(dir, d)| {
+                d.files
+                    .values()
+                    .try_fold(0usize, |sum, size| sum.checked_add(*size))
+                    .ok_or_else(|| overflow(dir))
             })
-            .map(|(_, d)| d.direct_files_size)
-            .sum()
+            .collect::<eyre::Result<_>>()?;
+        for dir in (1..self.dirs.len()).rev() {
+            let parent = self.dirs[dir].parent.expect("only the root has no parent");
+            sizes[parent] = sizes[parent]
+                .checked_add(sizes[dir])
+                .ok_or_else(|| overflow(parent))?;
+        }
+        Ok(sizes)
+    }
+
+    /// absolute path of `dir`, such as `/a/e`
+    fn path(&self, dir: usize) -> String {
+        let mut names = vec![];
+        let mut dir = dir;
+        while let Some(parent) = self.dirs[dir].parent {
+            names.push(self.dirs[dir].name);
+            dir = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
     }
 
-    fn sum_size_under_threshold(&self, threshold: usize) -> usize {
-        let direct_dirs: Vec<_> = self
+    /// the path and total size of every directory, a parent being before its children
+    pub fn dir_sizes(&self) -> eyre::Result<Vec<(String, usize)>> {
+        Ok(self
+            .total_sizes()?
+            .into_iter()
+            .enumerate()
+            .map(|(dir, size)| (self.path(dir), size))
+            .collect())
+    }
+
+    /// total size of the directory at `path`, if it is known
+    pub fn total_size(&self, path: &str) -> eyre::Result<Option<usize>> {
+        Ok(match self.find(path) {
+            Some(dir) => Some(self.total_sizes()?[dir]),
+            None => None,
+        })
+    }
+
+    /// the entries of `dir` as (name, size, subdirectory), in `listing` order
+    fn entries(
+        &self,
+        dir: usize,
+        sizes: &[usize],
+        listing: &Listing,
+    ) -> Vec<(&'a str, usize, Option<usize>)> {
+        let dir = &self.dirs[dir];
+        let mut entries: Vec<_> = dir
             .dirs
             .iter()
-            .filter(|(_, d)| d.direct_files_size <= threshold)
-            .filter(|(p, _)| self.get_total_size(p) <= threshold)
+            .map(|(name, d)| (*name, sizes[*d], Some(*d)))
+            .chain(dir.files.iter().map(|(name, size)| (*name, *size, None)))
             .collect();
+        entries.sort_by_key(|(name, _, _)| *name);
+        if listing.by_size {
+            entries.sort_by_key(|(_, size, _)| Reverse(*size));
+        }
+        entries
+    }
+
+    /// indented entries with their sizes, as drawn in the puzzle
+    pub fn tree(&self, listing: &Listing) -> eyre::Result<String> {
+        let sizes = self.total_sizes()?;
+        let mut tree = format!("- / (dir, size={})\n", sizes[ROOT]);
+        self.write_tree(&mut tree, ROOT, 1, &sizes, listing);
+        Ok(tree)
+    }
 
-        // FIXME : get_total_size is not as efficient as summing sizes all the way down (expect if threshold is low)
-        let mut filtered_dir_to_size: HashMap<Vec<&[u8]>, usize> =
-            HashMap::with_capacity(direct_dirs.len());
-        'main: for d in direct_dirs {
-            let path = d.0.clone();
-            if filtered_dir_to_size.contains_key(&path) {
-                continue 'main;
+    fn write_tree(
+        &self,
+        tree: &mut String,
+        dir: usize,
+        depth: usize,
+        sizes: &[usize],
+        listing: &Listing,
+    ) {
+        if !listing.shows(depth) {
+            return;
+        }
+        for (name, size, subdir) in self.entries(dir, sizes, listing) {
+            let kind = if subdir.is_some() { "dir" } else { "file" };
+            tree.push_str(&format!(
+                "{}- {name} ({kind}, size={size})\n",
+                "  ".repeat(depth)
+            ));
+            if let Some(subdir) = subdir {
+                self.write_tree(tree, subdir, depth + 1, sizes, listing);
             }
-            let size = self.get_total_size(&path);
-            filtered_dir_to_size.insert(path.clone(), size);
-
-            let mut path = path.clone();
-            while !path.is_empty() {
-                path.pop();
-                if filtered_dir_to_size.contains_key(&path) {
-                    continue 'main;
-                }
-                let size = self.get_total_size(&path);
-                if size > threshold {
-                    continue 'main;
-                }
-                filtered_dir_to_size.insert(path.clone(), size);
+        }
+    }
+
+    /// the size and path of every entry, as `du -a` prints them : contents before their directory
+    pub fn du(&self, listing: &Listing) -> eyre::Result<String> {
+        let sizes = self.total_sizes()?;
+        let mut lines = vec![];
+        self.collect_du(&mut lines, ROOT, "/".to_string(), 0, &sizes, listing);
+        if listing.by_size {
+            lines.sort_by_key(|(size, _)| Reverse(*size));
+        }
+        Ok(lines
+            .iter()
+            .map(|(size, path)| format!("{size}\t{path}\n"))
+            .collect())
+    }
+
+    fn collect_du(
+        &self,
+        lines: &mut Vec<(usize, String)>,
+        dir: usize,
+        path: String,
+        depth: usize,
+        sizes: &[usize],
+        listing: &Listing,
+    ) {
+        for (name, size, subdir) in self.entries(dir, sizes, &Listing::default()) {
+            let path = child_path(&path, name);
+            match subdir {
+                Some(subdir) => self.collect_du(lines, subdir, path, depth + 1, sizes, listing),
+                None if listing.shows(depth + 1) => lines.push((size, path)),
+                None => {}
             }
         }
-        filtered_dir_to_size.values().sum()
+        if listing.shows(depth) {
+            lines.push((sizes[dir], path));
+        }
     }
 
-    fn get_minimum_freed_storage(&self, capacity: usize, free_size_target: usize) -> usize {
-        let used = self.get_total_size(&vec![]);
-        let missing = free_size_target + used - capacity;
+    /// the directories summed by [Self::sum_size_under_threshold], as (path, size)
+    pub fn dirs_under_threshold(&self, threshold: usize) -> eyre::Result<Vec<(String, usize)>> {
+        Ok(self
+            .dir_sizes()?
+            .into_iter()
+            .filter(|(_, size)| *size <= threshold)
+            .collect())
+    }
 
-        // ugly, needs a proper TRIE structure
-        let all_dirs: HashSet<Vec<&[u8]>> = self
-            .dirs
+    pub fn sum_size_under_threshold(&self, threshold: usize) -> eyre::Result<usize> {
+        self.dirs_under_threshold(threshold)?
             .iter()
-            .flat_map(|(d, _)| {
-                (0usize..=d.len()).map(|i| d.iter().copied().take(i).collect::<Vec<&[u8]>>())
+            .try_fold(0usize, |sum, (_, size)| sum.checked_add(*size))
+            .with_context(|| {
+                format!(
+                    "the directories of at most {threshold} sum up past {}",
+                    usize::MAX
+                )
             })
-            .collect();
+    }
 
-        all_dirs
-            .iter()
-            .map(|d| self.get_total_size(d))
-            .filter(|s| *s >= missing)
-            .min()
-            .unwrap_or(0)
+    /// the smallest directory whose deletion frees enough, as (path, size)
+    pub fn dir_to_delete(
+        &self,
+        capacity: usize,
+        free_size_target: usize,
+    ) -> eyre::Result<(String, usize)> {
+        let sizes = self.total_sizes()?;
+        let used = sizes[ROOT];
+        let missing = free_size_target
+            .checked_add(used)
+            .with_context(|| format!("{used} used and {free_size_target} to free overflow"))?
+            .saturating_sub(capacity);
+        sizes
+            .into_iter()
+            .enumerate()
+            .filter(|(_, s)| *s >= missing)
+            .min_by_key(|(_, s)| *s)
+            .map(|(dir, size)| (self.path(dir), size))
+            .with_context(|| format!("no directory frees {missing} at least"))
     }
-}
 
-pub fn update_handled() {
-    let input = include_str!("../resources/day7_file_system.txt");
-    let mut fs = FileSystem::new();
-    for l in input.lines() {
-        fs.parse_line(l).expect("could parse {l}");
+    pub fn get_minimum_freed_storage(
+        &self,
+        capacity: usize,
+        free_size_target: usize,
+    ) -> eyre::Result<usize> {
+        Ok(self.dir_to_delete(capacity, free_size_target)?.1)
     }
-    let size_under_100000_sum = fs.sum_size_under_threshold(100000);
-    println!("sum of dir under 100000 size {size_under_100000_sum}");
+}
 
-    let freed_size = fs.get_minimum_freed_storage(70000000, 30000000);
-    println!("freed dir is {freed_size}");
+impl<'i> Solution<'i> for FileSystem<'i> {
+    fn parse(input: &'i str) -> eyre::Result<Self> {
+        let mut fs = FileSystem::new();
+        parse_lines(input, |l| fs.parse_line(l))?;
+        Ok(fs)
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(self.sum_size_under_threshold(SMALL_DIR_SIZE)?.into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(self
+            .get_minimum_freed_storage(DISK_CAPACITY, UPDATE_SIZE)?
+            .into())
+    }
+    fn check(input: &'i str) -> Vec<eyre::Report> {
+        let mut fs = FileSystem::new();
+        let mut problems = check_lines(input, |l| fs.check_line(l));
+        if let Err(e) = fs.end_ls() {
+            problems.push(e.wrap_err("at the end of the transcript"));
+        }
+        if let Err(e) = fs.total_sizes() {
+            problems.push(e);
+        }
+        problems
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use itertools::Itertools;
+    use proptest::prelude::*;
+    use std::collections::BTreeMap;
+
+    const TRANSCRIPT: &str = indoc! {"
+        $ cd /
+        $ ls
+        dir a
+        14848514 b.txt
+        8504156 c.dat
+        dir d
+        $ cd a
+        $ ls
+        dir e
+        29116 f
+        2557 g
+        62596 h.lst
+        $ cd e
+        $ ls
+        584 i
+        $ cd ..
+        $ cd ..
+        $ cd d
+        $ ls
+        4060174 j
+        8033020 d.log
+        5626152 d.ext
+        7214296 k
+    "};
 
     #[test]
     fn aoc_example_works() {
-        let input = indoc! {"
+        let fs = FileSystem::parse(TRANSCRIPT).unwrap();
+        assert_eq!(95437, fs.sum_size_under_threshold(100000).unwrap());
+        assert_eq!(
+            24933642,
+            fs.get_minimum_freed_storage(70000000, 30000000).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = FileSystem::parse("$ cd /\n$ ls\n12k a.txt\n")
+            .err()
+            .unwrap();
+        assert_eq!("line 3 : '12k a.txt'", error.to_string());
+        assert!(FileSystem::parse("$ cd /\n$ cd \n").is_err());
+    }
+
+    #[test]
+    fn file_system_tree_works() {
+        assert!(FileSystem::parse("$ cd /\n$ ls\n12\n").is_err());
+
+        let fs = FileSystem::parse(TRANSCRIPT).unwrap();
+        assert_eq!(Some(48381165), fs.total_size("/").unwrap());
+        assert_eq!(Some(94853), fs.total_size("/a").unwrap());
+        assert_eq!(Some(584), fs.total_size("/a/e").unwrap());
+        assert_eq!(None, fs.total_size("/e").unwrap());
+
+        // listed but never entered, or entered without being listed
+        let fs = FileSystem::parse(
+            "$ cd /\n$ ls\ndir a\n10 b\n$ cd c/d\n$ ls\n5 e\n$ cd /../..\n$ ls\n",
+        )
+        .unwrap();
+        assert_eq!(Some(0), fs.total_size("/a").unwrap());
+        assert_eq!(Some(5), fs.total_size("/c").unwrap());
+        assert_eq!(Some(15), fs.total_size("/").unwrap());
+        assert_eq!(
+            vec![
+                ("/".to_string(), 15),
+                ("/a".to_string(), 0),
+                ("/c".to_string(), 5),
+                ("/c/d".to_string(), 5)
+            ],
+            fs.dir_sizes().unwrap()
+        );
+    }
+
+    #[test]
+    fn listings_work() {
+        let fs = FileSystem::parse(TRANSCRIPT).unwrap();
+        assert_eq!(
+            vec![("/a".to_string(), 94853), ("/a/e".to_string(), 584)],
+            fs.dirs_under_threshold(100000).unwrap()
+        );
+        assert_eq!(
+            ("/d".to_string(), 24933642),
+            fs.dir_to_delete(70000000, 30000000).unwrap()
+        );
+
+        assert_eq!(
+            indoc! {"
+                - / (dir, size=48381165)
+                  - a (dir, size=94853)
+                    - e (dir, size=584)
+                      - i (file, size=584)
+                    - f (file, size=29116)
+                    - g (file, size=2557)
+                    - h.lst (file, size=62596)
+                  - b.txt (file, size=14848514)
+                  - c.dat (file, size=8504156)
+                  - d (dir, size=24933642)
+                    - d.ext (file, size=5626152)
+                    - d.log (file, size=8033020)
+                    - j (file, size=4060174)
+                    - k (file, size=7214296)
+            "},
+            fs.tree(&Listing::default()).unwrap()
+        );
+        let top = Listing {
+            by_size: true,
+            max_depth: Some(1),
+        };
+        assert_eq!(
+            indoc! {"
+                - / (dir, size=48381165)
+                  - d (dir, size=24933642)
+                  - b.txt (file, size=14848514)
+                  - c.dat (file, size=8504156)
+                  - a (dir, size=94853)
+            "},
+            fs.tree(&top).unwrap()
+        );
+
+        assert_eq!(
+            "94853\t/a\n14848514\t/b.txt\n8504156\t/c.dat\n24933642\t/d\n48381165\t/\n",
+            fs.du(&Listing {
+                max_depth: Some(1),
+                ..Listing::default()
+            })
+            .unwrap()
+        );
+        let du = fs.du(&Listing::default()).unwrap();
+        assert_eq!(
+            Some("584\t/a/e/i\n584\t/a/e\n29116\t/a/f\n"),
+            du.get(..du.find("2557").unwrap())
+        );
+        assert!(fs
+            .du(&top)
+            .unwrap()
+            .starts_with("48381165\t/\n24933642\t/d\n14848514\t/b.txt\n"));
+    }
+
+    #[test]
+    fn transcript_check_works() {
+        let transcript = indoc! {"
             $ cd /
             $ ls
             dir a
-            14848514 b.txt
-            8504156 c.dat
-            dir d
+            10 b
+            10 b
+            dir a
             $ cd a
-            $ ls
-            dir e
-            29116 f
-            2557 g
-            62596 h.lst
-            $ cd e
-            $ ls
-            584 i
+            5 f
             $ cd ..
             $ cd ..
-            $ cd d
+            $ cd y
+            $ cd /
             $ ls
-            4060174 j
-            8033020 d.log
-            5626152 d.ext
-            7214296 k
+            20 b
+            20 b
+            5 c
         "};
-        let mut fs = FileSystem::new();
-        for l in input.lines() {
-            fs.parse_line(l).expect("could parse {l}");
+        let fs = FileSystem::parse(transcript).unwrap();
+        assert_eq!(Some(30), fs.total_size("/").unwrap());
+
+        let problems: Vec<String> = FileSystem::check(&format!("{transcript}$ ls -l\n$ pwd\n"))
+            .iter()
+            .map(|e| format!("{e:#}"))
+            .collect();
+        assert_eq!(
+            vec![
+                "line 5 : '10 b': 'b' is listed twice by the same ls of '/'",
+                "line 6 : 'dir a': 'a' is listed twice by the same ls of '/'",
+                "line 8 : '5 f': output without a preceding ls",
+                "line 10 : '$ cd ..': cd .. above the root",
+                "line 11 : '$ cd y': cd into '/y' which was never listed",
+                "line 14 : '20 b': file 'b' of '/' was listed with size 10, not 20",
+                "line 15 : '20 b': 'b' is listed twice by the same ls of '/'",
+                "line 16 : '5 c': 'c' was not in the previous listing of '/'",
+                "line 17 : '$ ls -l': ls takes no arguments",
+                "line 18 : '$ pwd': unknown command 'pwd'",
+                "at the end of the transcript: the listing of '/' misses 2 entries of the previous one",
+            ],
+            problems
+        );
+
+        let consistent = "$ cd /\n$ ls\ndir a\n10 b\n$ ls\n10 b\ndir a\n";
+        assert!(FileSystem::check(consistent).is_empty());
+        assert_eq!(
+            Some(10),
+            FileSystem::parse(consistent)
+                .unwrap()
+                .total_size("/")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn overflows_are_errors() {
+        let transcript = "$ cd /\n$ ls\n18446744073709551615 a\n1 b\n";
+        let fs = FileSystem::parse(transcript).unwrap();
+        let error = fs.part1().unwrap_err();
+        assert_eq!("the total size of / overflows", error.to_string());
+        assert!(fs.part2().is_err());
+        assert_eq!(1, FileSystem::check(transcript).len());
+
+        let fs = FileSystem::parse("$ cd /\n$ ls\n18446744073709551615 a\n").unwrap();
+        assert_eq!(Answer::from(0usize), fs.part1().unwrap());
+        let error = fs.part2().unwrap_err();
+        assert_eq!(
+            "18446744073709551615 used and 30000000 to free overflow",
+            error.to_string()
+        );
+    }
+
+    /// files of each directory, the root being the empty path
+    type Tree = BTreeMap<Vec<usize>, BTreeMap<usize, usize>>;
+
+    const NAMES: [&str; 3] = ["a", "b", "c"];
+
+    fn dir_path(dir: &[usize]) -> String {
+        format!("/{}", dir.iter().map(|d| NAMES[*d]).join("/"))
+    }
+
+    /// transcript of a depth first exploration of `tree`, from `dir`
+    fn explore(tree: &Tree, dir: &[usize], transcript: &mut String) {
+        let subdirs: Vec<usize> = (0..NAMES.len())
+            .filter(|d| tree.contains_key(&[dir, &[*d]].concat()))
+            .collect();
+        transcript.push_str("$ ls\n");
+        for d in &subdirs {
+            transcript.push_str(&format!("dir {}\n", NAMES[*d]));
+        }
+        for (f, size) in &tree[dir] {
+            transcript.push_str(&format!("{size} {}.txt\n", NAMES[*f]));
+        }
+        for d in subdirs {
+            transcript.push_str(&format!("$ cd {}\n", NAMES[d]));
+            explore(tree, &[dir, &[d]].concat(), transcript);
+            transcript.push_str("$ cd ..\n");
+        }
+    }
+
+    fn transcript() -> impl Strategy<Value = (Tree, String)> {
+        prop::collection::vec(
+            (
+                prop::collection::vec(0..3usize, 0..4),
+                0..3usize,
+                1..100000usize,
+            ),
+            0..20,
+        )
+        .prop_map(|files| {
+            let mut tree = Tree::new();
+            for (dir, file, size) in files {
+                for depth in 0..dir.len() {
+                    tree.entry(dir[..depth].to_vec()).or_default();
+                }
+                tree.entry(dir).or_default().insert(file, size);
+            }
+            tree.entry(vec![]).or_default();
+            let mut transcript = "$ cd /\n".to_string();
+            explore(&tree, &[], &mut transcript);
+            (tree, transcript)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn transcript_can_be_read((tree, transcript) in transcript()) {
+            prop_assert!(FileSystem::check(&transcript).is_empty());
+            let fs = FileSystem::parse(&transcript).unwrap();
+            let sizes: Vec<(String, usize)> = tree
+                .keys()
+                .map(|dir| {
+                    let size = tree
+                        .iter()
+                        .filter(|(d, _)| d.starts_with(dir))
+                        .flat_map(|(_, files)| files.values())
+                        .sum();
+                    (dir_path(dir), size)
+                })
+                .sorted()
+                .collect();
+            for (path, size) in &sizes {
+                prop_assert_eq!(Some(*size), fs.total_size(path).unwrap());
+            }
+            prop_assert_eq!(sizes, fs.dir_sizes().unwrap().into_iter().sorted().collect_vec());
         }
-        assert_eq!(95437, fs.sum_size_under_threshold(100000));
-        assert_eq!(24933642, fs.get_minimum_freed_storage(70000000, 30000000));
     }
 }
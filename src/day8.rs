@@ -1,35 +1,36 @@
+use crate::parsing::{at, check_chars, or_parse_error};
+use crate::solution::{Answer, Solution};
+use eyre::{bail, eyre, ContextCompat};
 use std::cmp::min;
 use std::str::FromStr;
 
-struct ForestGrid {
+pub struct ForestGrid {
     trees: Vec<Vec<u8>>,
 }
 impl FromStr for ForestGrid {
     type Err = eyre::Error;
 
     fn from_str(s: &str) -> eyre::Result<Self> {
-        let trees: Vec<_> = s
+        let trees: Vec<Vec<u8>> = s
             .lines()
-            .filter(|l| !l.trim().is_empty())
-            .map(|l| {
-                l.chars()
-                    .filter_map(|c| match c {
-                        '0' => Some(0),
-                        '1' => Some(1),
-                        '2' => Some(2),
-                        '3' => Some(3),
-                        '4' => Some(4),
-                        '5' => Some(5),
-                        '6' => Some(6),
-                        '7' => Some(7),
-                        '8' => Some(8),
-                        '9' => Some(9),
-                        _ => None,
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| {
+                l.trim_end()
+                    .chars()
+                    .enumerate()
+                    .map(|(j, c)| match c.to_digit(10) {
+                        Some(h) => Ok(h as u8),
+                        None => Err(eyre!("invalid tree height '{c}' at {}", at(i, j))),
                     })
                     .collect()
             })
-            .collect();
+            .collect::<eyre::Result<_>>()?;
 
+        let width = trees.first().map(|r| r.len()).context("empty forest")?;
+        if let Some(row) = trees.iter().position(|r| r.len() != width) {
+            bail!("row {} is not {width} trees wide", row + 1);
+        }
         Ok(Self { trees })
     }
 }
@@ -45,9 +46,9 @@ impl ForestGrid {
             .collect()
     }
 
-    fn is_hidden(&self, row_idx: usize, col_idx: usize) -> bool {
+    pub fn is_hidden(&self, row_idx: usize, col_idx: usize) -> bool {
         let max_row_idx = self.trees.len() - 1;
-        let max_col_idx = self.trees.get(0).map(|r| r.len() - 1).unwrap_or(0);
+        let max_col_idx = self.trees.first().map(|r| r.len() - 1).unwrap_or(0);
 
         if row_idx == 0 || row_idx == max_row_idx || col_idx == 0 || col_idx == max_col_idx {
             return false;
@@ -64,18 +65,18 @@ impl ForestGrid {
             && row
                 .iter()
                 .rev()
-                .take(max_row_idx - col_idx)
+                .take(max_col_idx - col_idx)
                 .any(|h| *h >= height)
             && col.iter().take(row_idx).any(|h| *h >= height)
             && col
                 .iter()
                 .rev()
-                .take(max_col_idx - row_idx)
+                .take(max_row_idx - row_idx)
                 .any(|h| *h >= height)
     }
-    fn count_visible_trees(&self) -> usize {
+    pub fn count_visible_trees(&self) -> usize {
         let max_row_idx = self.trees.len() - 1;
-        let max_col_idx = self.trees.get(0).map(|r| r.len() - 1).unwrap_or(0);
+        let max_col_idx = self.trees.first().map(|r| r.len() - 1).unwrap_or(0);
 
         (0..=max_row_idx)
             .flat_map(move |i| (0..=max_col_idx).map(move |j| (i, j)))
@@ -83,7 +84,7 @@ impl ForestGrid {
             .count()
     }
 
-    fn compute_view_score(&self, row_idx: usize, col_idx: usize) -> usize {
+    pub fn compute_view_score(&self, row_idx: usize, col_idx: usize) -> usize {
         let col = self.get_column(col_idx).expect("too right");
         let row = self.get_row(row_idx).expect("too down");
         let height = self.trees[row_idx][col_idx];
@@ -98,7 +99,7 @@ impl ForestGrid {
         );
         let right = if col_idx < row.len() - 1 {
             min(
-                1 + row[col_idx + 1..col.len()]
+                1 + row[col_idx + 1..]
                     .iter()
                     .take_while(|h| **h < height)
                     .count(),
@@ -119,7 +120,7 @@ impl ForestGrid {
         let down = if row_idx < col.len() - 1 {
             min(
                 col.len() - 1 - row_idx,
-                1 + col[row_idx + 1..row.len()]
+                1 + col[row_idx + 1..]
                     .iter()
                     .take_while(|h| **h < height)
                     .count(),
@@ -131,7 +132,7 @@ impl ForestGrid {
         left * right * up * down
     }
 
-    fn compute_best_view_score(&self) -> usize {
+    pub fn compute_best_view_score(&self) -> usize {
         (0..self.trees.len())
             .flat_map(move |row_idx| {
                 (0..self.trees[0].len())
@@ -142,20 +143,26 @@ impl ForestGrid {
     }
 }
 
-pub fn build_tree_house() {
-    let forest: ForestGrid = include_str!("../resources/day8_trees_heights.txt")
-        .parse()
-        .expect("could not parse forest");
-    let visible_trees_count = forest.count_visible_trees();
-    println!("number of visible trees : {visible_trees_count}");
-
-    let best_view_score = forest.compute_best_view_score();
-    println!("best view score  : {best_view_score}");
+impl Solution<'_> for ForestGrid {
+    fn parse(input: &str) -> eyre::Result<Self> {
+        input.parse()
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        Ok(self.count_visible_trees().into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        Ok(self.compute_best_view_score().into())
+    }
+    fn check(input: &str) -> Vec<eyre::Report> {
+        or_parse_error(check_chars(input, "0123456789"), || input.parse::<Self>())
+    }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use itertools::Itertools;
+    use proptest::prelude::*;
 
     #[test]
     fn aoc_example_works() {
@@ -177,4 +184,65 @@ mod tests {
         assert_eq!(4, forest.compute_view_score(1, 2));
         assert_eq!(8, forest.compute_view_score(3, 2));
     }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let error = "303\n2x5\n".parse::<ForestGrid>().err().unwrap();
+        assert_eq!(
+            "invalid tree height 'x' at line 2, column 2",
+            error.to_string()
+        );
+        assert!("303\n25\n".parse::<ForestGrid>().is_err());
+    }
+
+    #[test]
+    fn non_square_forests_work() {
+        let forest: ForestGrid = "30373\n25512\n65332\n".parse().unwrap();
+        assert_eq!(14, forest.count_visible_trees());
+        assert_eq!(2, forest.compute_best_view_score());
+    }
+
+    fn forest() -> impl Strategy<Value = Vec<Vec<u8>>> {
+        (1..8usize, 1..8usize).prop_flat_map(|(rows, cols)| {
+            prop::collection::vec(prop::collection::vec(0..10u8, cols), rows)
+        })
+    }
+
+    /// trees seen from (`row`, `col`) looking along (`dr`, `dc`), and whether the edge is seen
+    fn look(trees: &[Vec<u8>], row: usize, col: usize, (dr, dc): (isize, isize)) -> (usize, bool) {
+        let height = trees[row][col];
+        let (mut r, mut c, mut seen) = (row as isize + dr, col as isize + dc, 0);
+        while let Some(h) = trees
+            .get(r as usize)
+            .and_then(|trees| trees.get(c as usize))
+            .filter(|_| r >= 0 && c >= 0)
+        {
+            seen += 1;
+            if *h >= height {
+                return (seen, false);
+            }
+            (r, c) = (r + dr, c + dc);
+        }
+        (seen, true)
+    }
+
+    proptest! {
+        #[test]
+        fn forest_can_be_read(trees in forest()) {
+            let grid = trees.iter().map(|row| row.iter().join("")).join("\n");
+            let forest: ForestGrid = grid.parse().unwrap();
+            let directions = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+            let positions = (0..trees.len()).cartesian_product(0..trees[0].len());
+            let visible = positions
+                .clone()
+                .filter(|(r, c)| directions.iter().any(|d| look(&trees, *r, *c, *d).1))
+                .count();
+            let best_score: usize = positions
+                .map(|(r, c)| directions.iter().map(|d| look(&trees, r, c, *d).0).product::<usize>())
+                .max()
+                .unwrap();
+            prop_assert_eq!(visible, forest.count_visible_trees());
+            prop_assert_eq!(best_score, forest.compute_best_view_score());
+        }
+    }
 }
@@ -1,4 +1,6 @@
 use crate::day9::Direction::{Down, Left, Right, Up};
+use crate::parsing::{check_lines, parse_lines};
+use crate::solution::{Answer, Solution};
 use eyre::{eyre, ContextCompat};
 use itertools::Itertools;
 use std::cmp::{max, min};
@@ -12,8 +14,8 @@ struct Point {
     y: isize,
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Knot {
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Knot {
     head: Point,
     tail: Point,
 }
@@ -179,35 +181,50 @@ fn move_head(knots: &mut [Knot], dir: (isize, isize)) {
     move_head(&mut knots[1..], (dx, dy));
 }
 
-fn count_tail_positions(path: &str, rope: &mut [Knot]) -> usize {
-    let mut pos: HashSet<Point> = HashSet::new();
+pub fn count_tail_positions(path: &str, rope: &mut [Knot]) -> eyre::Result<usize> {
+    Ok(follow_path(&parse_lines(path, str::parse)?, rope))
+}
+
+fn follow_path(moves: &[Move], rope: &mut [Knot]) -> usize {
     let rlen = rope.len();
-    for mv in path.lines().filter_map(|l| l.parse::<Move>().ok()) {
+    let mut pos: HashSet<Point> = HashSet::from([rope[rlen - 1].tail]);
+    for mv in moves {
         for _i in 0..mv.len {
             move_head(rope, mv.dir.get_d());
             pos.insert(rope[rlen - 1].tail);
         }
-        // println!("========================\n{}\n===================", display_knots(rope));
     }
 
     pos.len()
 }
-pub fn simulate_bridge() {
-    let path = include_str!("../resources/day9_path.txt");
-    let mut small_rope = [Knot::new()];
-    let tail_pos_count = count_tail_positions(path, &mut small_rope);
-
-    println!("number of unique pos of tail : {tail_pos_count}");
+pub struct BridgePath {
+    moves: Vec<Move>,
+}
 
-    let mut long_rope = [Knot::new(); 9];
-    let tail_pos_count = count_tail_positions(path, &mut long_rope);
-    println!("number of unique pos of tail : {tail_pos_count}");
+impl Solution<'_> for BridgePath {
+    fn parse(path: &str) -> eyre::Result<Self> {
+        Ok(Self {
+            moves: parse_lines(path, str::parse)?,
+        })
+    }
+    fn part1(&self) -> eyre::Result<Answer> {
+        let mut small_rope = [Knot::new()];
+        Ok(follow_path(&self.moves, &mut small_rope).into())
+    }
+    fn part2(&self) -> eyre::Result<Answer> {
+        let mut long_rope = [Knot::new(); 9];
+        Ok(follow_path(&self.moves, &mut long_rope).into())
+    }
+    fn check(path: &str) -> Vec<eyre::Report> {
+        check_lines(path, str::parse::<Move>)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     #[test]
     fn aoc_examples_works() {
@@ -222,9 +239,9 @@ mod tests {
             R 2
         "};
         let mut knot = [Knot::new()];
-        assert_eq!(13, count_tail_positions(path, &mut knot));
+        assert_eq!(13, count_tail_positions(path, &mut knot).unwrap());
         let mut knots = [Knot::new(); 9];
-        assert_eq!(1, count_tail_positions(path, &mut knots));
+        assert_eq!(1, count_tail_positions(path, &mut knots).unwrap());
 
         let path = indoc! {"
             R 5
@@ -237,6 +254,64 @@ mod tests {
             U 20
         "};
         let mut knots = [Knot::new(); 9];
-        assert_eq!(36, count_tail_positions(path, &mut knots));
+        assert_eq!(36, count_tail_positions(path, &mut knots).unwrap());
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let mut knots = [Knot::new(); 9];
+        assert!(count_tail_positions("R 4\nX 2\n", &mut knots).is_err());
+    }
+
+    #[test]
+    fn empty_paths_leave_the_tail_at_start() {
+        assert_eq!(1, count_tail_positions("", &mut [Knot::new()]).unwrap());
+        assert_eq!(1, count_tail_positions("", &mut [Knot::new(); 9]).unwrap());
+    }
+
+    fn path() -> impl Strategy<Value = Vec<((isize, isize), usize)>> {
+        let direction = prop::sample::select(vec![(0, 1), (0, -1), (-1, 0), (1, 0)]);
+        prop::collection::vec((direction, 1..10usize), 0..30)
+    }
+
+    /// positions of the last of `len` knots, each one following the previous one
+    fn tail_positions(path: &[((isize, isize), usize)], len: usize) -> usize {
+        let mut knots = vec![(0isize, 0isize); len];
+        let mut positions = HashSet::from([(0, 0)]);
+        for ((dx, dy), steps) in path {
+            for _ in 0..*steps {
+                knots[0] = (knots[0].0 + dx, knots[0].1 + dy);
+                for i in 1..len {
+                    let (x, y) = (knots[i - 1].0 - knots[i].0, knots[i - 1].1 - knots[i].1);
+                    if x.abs() > 1 || y.abs() > 1 {
+                        knots[i] = (knots[i].0 + x.signum(), knots[i].1 + y.signum());
+                    }
+                }
+                positions.insert(knots[len - 1]);
+            }
+        }
+        positions.len()
+    }
+
+    proptest! {
+        #[test]
+        fn path_can_be_read(path in path(), len in 1..10usize) {
+            let moves = path
+                .iter()
+                .map(|(d, steps)| {
+                    let dir = match d {
+                        (0, 1) => 'U',
+                        (0, _) => 'D',
+                        (-1, _) => 'L',
+                        _ => 'R',
+                    };
+                    format!("{dir} {steps}\n")
+                })
+                .collect::<String>();
+            prop_assert_eq!(
+                tail_positions(&path, len + 1),
+                count_tail_positions(&moves, &mut vec![Knot::new(); len]).unwrap()
+            );
+        }
     }
 }
@@ -0,0 +1,125 @@
+use eyre::{eyre, Context};
+use std::borrow::Cow;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_RESOURCES_DIR: &str = "resources";
+
+/// where a day puzzle input is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// `dayN_*.txt` in the given directory, falling back to the input embedded at build time
+    Resources(PathBuf),
+    File(PathBuf),
+    Stdin,
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        Self::Resources(PathBuf::from(DEFAULT_RESOURCES_DIR))
+    }
+}
+
+impl InputSource {
+    /// `-` stands for stdin
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => Self::Stdin,
+            path => Self::File(PathBuf::from(path)),
+        }
+    }
+
+    pub fn load(&self, day: usize, embedded: &'static str) -> eyre::Result<Cow<'static, str>> {
+        match self {
+            Self::Resources(dir) => match find_day_file(dir, day)? {
+                Some(path) => read_file(&path).map(Cow::Owned),
+                None => Ok(Cow::Borrowed(embedded)),
+            },
+            Self::File(path) => read_file(path).map(Cow::Owned),
+            Self::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .context("reading input from stdin")?;
+                Ok(Cow::Owned(input))
+            }
+        }
+    }
+}
+
+fn read_file(path: &Path) -> eyre::Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("reading input {}", path.display()))
+}
+
+/// looks for a single `dayN_*.txt` file in `dir`, a missing directory meaning no file
+fn find_day_file(dir: &Path, day: usize) -> eyre::Result<Option<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(None);
+    }
+    let prefix = format!("day{day}_");
+    let mut candidates = vec![];
+    for entry in std::fs::read_dir(dir).with_context(|| format!("listing {}", dir.display()))? {
+        let path = entry?.path();
+        let matches = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.starts_with(&prefix) && n.ends_with(".txt"))
+            .unwrap_or(false);
+        if matches {
+            candidates.push(path);
+        }
+    }
+    match candidates.len() {
+        0 => Ok(None),
+        1 => Ok(candidates.pop()),
+        _ => {
+            candidates.sort();
+            Err(eyre!(
+                "several inputs for day {day} : {}",
+                candidates
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resources_lookup_works() {
+        let dir = std::env::temp_dir().join(format!("aoc2022_input_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day1_mine.txt"), "1000\n").unwrap();
+        std::fs::write(dir.join("day10_mine.txt"), "noop\n").unwrap();
+        std::fs::write(dir.join("day2_a.txt"), "A X\n").unwrap();
+        std::fs::write(dir.join("day2_b.txt"), "B Y\n").unwrap();
+
+        let source = InputSource::Resources(dir.clone());
+        assert_eq!("1000\n", source.load(1, "embedded").unwrap());
+        assert_eq!("noop\n", source.load(10, "embedded").unwrap());
+        assert!(source.load(2, "embedded").is_err());
+        assert_eq!("embedded", source.load(3, "embedded").unwrap());
+
+        let missing = InputSource::Resources(dir.join("missing"));
+        assert_eq!("embedded", missing.load(1, "embedded").unwrap());
+
+        assert_eq!(
+            "A X\n",
+            InputSource::from_arg(dir.join("day2_a.txt").to_str().unwrap())
+                .load(2, "")
+                .unwrap()
+        );
+        assert!(
+            InputSource::from_arg(dir.join("day4.txt").to_str().unwrap())
+                .load(4, "")
+                .is_err()
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
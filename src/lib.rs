@@ -0,0 +1,227 @@
+//! Advent of Code 2022 : puzzle input models and solvers, one module per day
+//!
+//! every day implements [solution::Solution], [DAYS] registering them all.
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod input;
+pub mod parsing;
+pub mod solution;
+
+use crate::solution::{check, solve, Answers, Parts};
+
+pub const LAST_DAY: usize = 25;
+
+pub struct Day {
+    pub number: usize,
+    /// fallback input, embedded at build time
+    pub input: &'static str,
+    pub solve: fn(&str, Parts) -> eyre::Result<Answers>,
+    /// structural problems of an input, see [solution::Solution::check]
+    pub check: fn(&str) -> Vec<eyre::Report>,
+}
+
+pub const DAYS: [Day; LAST_DAY] = [
+    Day {
+        number: 1,
+        input: include_str!("../resources/day1_calories.txt"),
+        solve: |input, parts| solve::<day1::Inventory>(input, parts),
+        check: |input| check::<day1::Inventory>(input),
+    },
+    Day {
+        number: 2,
+        input: include_str!("../resources/day2_rock_paper_scissors.txt"),
+        solve: |input, parts| solve::<day2::StrategyGuide>(input, parts),
+        check: |input| check::<day2::StrategyGuide>(input),
+    },
+    Day {
+        number: 3,
+        input: include_str!("../resources/day3_rucksacks.txt"),
+        solve: |input, parts| solve::<day3::Rucksacks>(input, parts),
+        check: |input| check::<day3::Rucksacks>(input),
+    },
+    Day {
+        number: 4,
+        input: include_str!("../resources/day4_assignments.txt"),
+        solve: |input, parts| solve::<day4::Assignments>(input, parts),
+        check: |input| check::<day4::Assignments>(input),
+    },
+    Day {
+        number: 5,
+        input: include_str!("../resources/day5_stocks_and_moves.txt"),
+        solve: |input, parts| solve::<day5::SupplyStacks>(input, parts),
+        check: |input| check::<day5::SupplyStacks>(input),
+    },
+    Day {
+        number: 6,
+        input: include_str!("../resources/day6_signal.txt"),
+        solve: |input, parts| solve::<day6::Signal>(input, parts),
+        check: |input| check::<day6::Signal>(input),
+    },
+    Day {
+        number: 7,
+        input: include_str!("../resources/day7_file_system.txt"),
+        solve: |input, parts| solve::<day7::FileSystem>(input, parts),
+        check: |input| check::<day7::FileSystem>(input),
+    },
+    Day {
+        number: 8,
+        input: include_str!("../resources/day8_trees_heights.txt"),
+        solve: |input, parts| solve::<day8::ForestGrid>(input, parts),
+        check: |input| check::<day8::ForestGrid>(input),
+    },
+    Day {
+        number: 9,
+        input: include_str!("../resources/day9_path.txt"),
+        solve: |input, parts| solve::<day9::BridgePath>(input, parts),
+        check: |input| check::<day9::BridgePath>(input),
+    },
+    Day {
+        number: 10,
+        input: include_str!("../resources/day10_source.txt"),
+        solve: |input, parts| solve::<day10::Computer>(input, parts),
+        check: |input| check::<day10::Computer>(input),
+    },
+    Day {
+        number: 11,
+        input: include_str!("../resources/day11_monkeys.txt"),
+        solve: |input, parts| solve::<day11::Game>(input, parts),
+        check: |input| check::<day11::Game>(input),
+    },
+    Day {
+        number: 12,
+        input: include_str!("../resources/day12_elevations.txt"),
+        solve: |input, parts| solve::<day12::Map>(input, parts),
+        check: |input| check::<day12::Map>(input),
+    },
+    Day {
+        number: 13,
+        input: include_str!("../resources/day13_pairs.txt"),
+        solve: |input, parts| solve::<day13::DistressSignal>(input, parts),
+        check: |input| check::<day13::DistressSignal>(input),
+    },
+    Day {
+        number: 14,
+        input: include_str!("../resources/day14_rocks.txt"),
+        solve: |input, parts| solve::<day14::RockScan>(input, parts),
+        check: |input| check::<day14::RockScan>(input),
+    },
+    Day {
+        number: 15,
+        input: include_str!("../resources/day15_sensors_beacons.txt"),
+        solve: |input, parts| solve::<day15::SensorsReport>(input, parts),
+        check: |input| check::<day15::SensorsReport>(input),
+    },
+    Day {
+        number: 16,
+        input: include_str!("../resources/day16_volcano.txt"),
+        solve: |input, parts| solve::<day16::Volcano>(input, parts),
+        check: |input| check::<day16::Volcano>(input),
+    },
+    Day {
+        number: 17,
+        input: include_str!("../resources/day17_moves.txt"),
+        solve: |input, parts| solve::<day17::Cave>(input, parts),
+        check: |input| check::<day17::Cave>(input),
+    },
+    Day {
+        number: 18,
+        input: include_str!("../resources/day18_droplets.txt"),
+        solve: |input, parts| solve::<day18::Droplet>(input, parts),
+        check: |input| check::<day18::Droplet>(input),
+    },
+    Day {
+        number: 19,
+        input: include_str!("../resources/day19_blueprints.txt"),
+        solve: |input, parts| solve::<day19::Blueprints>(input, parts),
+        check: |input| check::<day19::Blueprints>(input),
+    },
+    Day {
+        number: 20,
+        input: include_str!("../resources/day20_encrypted_coordinates.txt"),
+        solve: |input, parts| solve::<day20::EncryptedFile>(input, parts),
+        check: |input| check::<day20::EncryptedFile>(input),
+    },
+    Day {
+        number: 21,
+        input: include_str!("../resources/day21_shouting.txt"),
+        solve: |input, parts| solve::<day21::Riddle>(input, parts),
+        check: |input| check::<day21::Riddle>(input),
+    },
+    Day {
+        number: 22,
+        input: include_str!("../resources/day22_map_password.txt"),
+        solve: |input, parts| solve::<day22::Map>(input, parts),
+        check: |input| check::<day22::Map>(input),
+    },
+    Day {
+        number: 23,
+        input: include_str!("../resources/day23_grove.txt"),
+        solve: |input, parts| solve::<day23::Grove>(input, parts),
+        check: |input| check::<day23::Grove>(input),
+    },
+    Day {
+        number: 24,
+        input: include_str!("../resources/day24_blizzard_valley.txt"),
+        solve: |input, parts| solve::<day24::Valley>(input, parts),
+        check: |input| check::<day24::Valley>(input),
+    },
+    Day {
+        number: 25,
+        input: include_str!("../resources/day25_snafus.txt"),
+        solve: |input, parts| solve::<day25::FuelRequirements>(input, parts),
+        check: |input| check::<day25::FuelRequirements>(input),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn no_input_makes_a_parser_panic(
+            day in 0..LAST_DAY,
+            bytes in prop::collection::vec(any::<u8>(), 0..300),
+        ) {
+            let _ = (DAYS[day].check)(&String::from_utf8_lossy(&bytes));
+        }
+
+        #[test]
+        fn no_altered_input_makes_a_parser_panic(
+            day in 0..LAST_DAY,
+            edits in prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8),
+        ) {
+            let mut bytes = DAYS[day].input.as_bytes().to_vec();
+            let len = bytes.len();
+            for (at, byte) in edits {
+                bytes[at.index(len)] = byte;
+            }
+            let _ = (DAYS[day].check)(&String::from_utf8_lossy(&bytes));
+        }
+    }
+}
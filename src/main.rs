@@ -1,53 +1,185 @@
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
-fn main() {
-    day1::handle_elves_food();
-    day2::play_rock_paper_scissors();
-    day3::arrange_ruckacks();
-    day4::clean_camp();
-    day5::supply_stack();
-    day6::detect_signal();
-    day7::update_handled();
-    day8::build_tree_house();
-    day9::simulate_bridge();
-    day10::decode_signal();
-    day11::chase_monkeys();
-    day12::climb_hills();
-    day13::distress_signal();
-    day14::avoid_sand();
-    day15::find_beacons();
-    day16::escape_volcano();
-    day17::tetris_rock();
-    day18::observe_boulders();
-    day19::collect_geodes();
-    day20::reach_elves();
-    day21::solve_riddles();
-    day22::decode_password();
-    day23::plant_grove();
-    day24::escape_valley();
-    day25::calibrate_bob();
+mod cli;
+mod report;
+mod verify;
+
+use crate::cli::{Args, Mode, USAGE};
+use crate::report::{summary_table, write_report, write_stats_report};
+use crate::verify::{print_verdicts, verify, ExpectedAnswers};
+use aoc2022::day1::InventoryStats;
+use aoc2022::day5::{
+    read_stock_and_moves, replay, Crane, CrateMover9000, CrateMover9001, Validation,
+};
+use aoc2022::day7::{FileSystem, Listing, DISK_CAPACITY, SMALL_DIR_SIZE, UPDATE_SIZE};
+use aoc2022::solution::{Answers, Solution};
+use aoc2022::{Day, DAYS};
+use eyre::{eyre, WrapErr};
+use rayon::prelude::*;
+use std::time::Instant;
+
+fn solve_day(day: &Day, args: &Args) -> eyre::Result<Answers> {
+    let input = args.input.load(day.number, day.input)?;
+    (day.solve)(&input, args.parts).wrap_err_with(|| format!("day {}", day.number))
+}
+
+/// prints the problems of each selected input, failing when any was found
+fn check_days(days: &[&Day], args: &Args) -> eyre::Result<()> {
+    let mut invalid = 0;
+    for day in days {
+        let input = args.input.load(day.number, day.input)?;
+        let problems = (day.check)(&input);
+        if problems.is_empty() {
+            println!("day {:>2} : ok", day.number);
+            continue;
+        }
+        invalid += 1;
+        println!("day {:>2} : {} problem(s)", day.number, problems.len());
+        for problem in problems {
+            println!("  {problem:#}");
+        }
+    }
+    if invalid > 0 {
+        return Err(eyre!("{invalid} input(s) are invalid"));
+    }
+    Ok(())
+}
+
+fn print_stats(top: usize, args: &Args) -> eyre::Result<()> {
+    let input = args.input.load(1, DAYS[0].input)?;
+    let stats = InventoryStats::new(&input, top).wrap_err("day 1")?;
+    print!("{stats}");
+    if let Some(path) = &args.report {
+        write_stats_report(&stats, path)?;
+    }
+    Ok(())
+}
+
+/// prints the crate stacks of day 5 after `step` moves, for each selected crane
+fn print_step(step: usize, args: &Args) -> eyre::Result<()> {
+    let input = args.input.load(5, DAYS[4].input)?;
+    let (stock, moves) = read_stock_and_moves(&input).wrap_err("day 5")?;
+    if step > moves.len() {
+        return Err(eyre!("there are only {} moves", moves.len()));
+    }
+    let cranes: [(bool, usize, &dyn Crane); 2] = [
+        (args.parts.first(), 9000, &CrateMover9000),
+        (args.parts.second(), 9001, &CrateMover9001),
+    ];
+    for (selected, model, crane) in cranes {
+        if !selected {
+            continue;
+        }
+        let mut previous = stock.clone();
+        let mut state = stock.clone();
+        for next in replay(stock.clone(), &moves, crane, Validation::Strict).take(step) {
+            previous = std::mem::replace(&mut state, next?);
+        }
+        println!("--- CrateMover {model}, after {step} move(s) ---");
+        if step > 0 {
+            println!(
+                "{} : stacks {:?} changed",
+                moves[step - 1],
+                previous.changed_stacks(&state)
+            );
+        }
+        println!("{state}");
+    }
+    Ok(())
+}
+
+/// prints the day 7 file system, then the directories chosen by each selected part
+fn print_listing(listing: &Listing, du: bool, args: &Args) -> eyre::Result<()> {
+    let input = args.input.load(7, DAYS[6].input)?;
+    let fs = FileSystem::parse(&input).wrap_err("day 7")?;
+    if du {
+        print!("{}", fs.du(listing)?);
+    } else {
+        print!("{}", fs.tree(listing)?);
+    }
+    if args.parts.first() {
+        let dirs = fs.dirs_under_threshold(SMALL_DIR_SIZE)?;
+        let total = fs.sum_size_under_threshold(SMALL_DIR_SIZE)?;
+        println!("--- directories of at most {SMALL_DIR_SIZE}, summing to {total} ---");
+        for (path, size) in dirs {
+            println!("{size}\t{path}");
+        }
+    }
+    if args.parts.second() {
+        let (path, size) = fs.dir_to_delete(DISK_CAPACITY, UPDATE_SIZE)?;
+        println!("--- smallest directory to delete for the update ---");
+        println!("{size}\t{path}");
+    }
+    Ok(())
+}
+
+fn print_answers(day: usize, answers: &Answers) {
+    println!("--- day {day} ---");
+    if let Some(answer) = &answers.part1 {
+        println!("part 1 : {answer}");
+    }
+    if let Some(answer) = &answers.part2 {
+        println!("part 2 : {answer}");
+    }
+}
+
+fn main() -> eyre::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    let args = Args::parse(args)?;
+    let days: Vec<&Day> = DAYS
+        .iter()
+        .filter(|d| args.days.contains(&d.number))
+        .collect();
+    let expected = match &args.mode {
+        Mode::Run => None,
+        Mode::Verify { answers } => Some(ExpectedAnswers::load(answers)?),
+        Mode::Check => return check_days(&days, &args),
+        Mode::Stats { top } => return print_stats(*top, &args),
+        Mode::Step { step } => return print_step(*step, &args),
+        Mode::Tree { listing } => return print_listing(listing, false, &args),
+        Mode::Du { listing } => return print_listing(listing, true, &args),
+    };
+    let print = expected.is_none();
+
+    let start = Instant::now();
+    let solved: Vec<(usize, Answers)> = if args.parallel {
+        // each day keeps its own timings, answers are printed once all are known
+        let solved = days
+            .par_iter()
+            .map(|day| Ok((day.number, solve_day(day, &args)?)))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        if print {
+            for (day, answers) in &solved {
+                print_answers(*day, answers);
+            }
+        }
+        solved
+    } else {
+        let mut solved = Vec::with_capacity(days.len());
+        for day in days {
+            let answers = solve_day(day, &args)?;
+            if print {
+                print_answers(day.number, &answers);
+            }
+            solved.push((day.number, answers));
+        }
+        solved
+    };
+    let elapsed = start.elapsed();
+
+    if let Some(path) = &args.report {
+        write_report(&solved, path)?;
+    }
+    match expected {
+        None => {
+            println!("\n{}", summary_table(&solved));
+            if args.parallel {
+                println!("wall clock : {:.3}ms", elapsed.as_secs_f64() * 1000.0);
+            }
+            Ok(())
+        }
+        Some(expected) => print_verdicts(&verify(&solved, &expected)),
+    }
 }
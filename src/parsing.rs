@@ -0,0 +1,98 @@
+use eyre::{eyre, WrapErr};
+
+/// human readable position of an error, from 0-based indexes
+pub fn at(line_idx: usize, column_idx: usize) -> String {
+    format!("line {}, column {}", line_idx + 1, column_idx + 1)
+}
+
+pub fn at_line(line_idx: usize) -> String {
+    format!("line {}", line_idx + 1)
+}
+
+fn located_lines<'i, T, F: FnMut(&'i str) -> eyre::Result<T>>(
+    input: &'i str,
+    mut parse: F,
+) -> impl Iterator<Item = eyre::Result<T>> + use<'i, T, F> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(move |(i, l)| parse(l).wrap_err_with(|| format!("{} : '{l}'", at_line(i))))
+}
+
+/// parses every non blank line of `input`, an error being located by its line number
+pub fn parse_lines<'i, T>(
+    input: &'i str,
+    parse: impl FnMut(&'i str) -> eyre::Result<T>,
+) -> eyre::Result<Vec<T>> {
+    located_lines(input, parse).collect()
+}
+
+/// every error [parse_lines] could stumble on, instead of the first one only
+pub fn check_lines<'i, T>(
+    input: &'i str,
+    parse: impl FnMut(&'i str) -> eyre::Result<T>,
+) -> Vec<eyre::Report> {
+    located_lines(input, parse)
+        .filter_map(Result::err)
+        .collect()
+}
+
+/// every character of a grid drawing which is not one of `allowed`
+pub fn check_chars(input: &str, allowed: &str) -> Vec<eyre::Report> {
+    input
+        .lines()
+        .enumerate()
+        .flat_map(|(i, l)| {
+            l.trim_end()
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| !allowed.contains(*c))
+                .map(move |(j, c)| eyre!("unexpected '{c}' at {}", at(i, j)))
+        })
+        .collect()
+}
+
+/// `problems` when there are some, else the error of a full `parse` if any
+pub fn or_parse_error<T>(
+    problems: Vec<eyre::Report>,
+    parse: impl FnOnce() -> eyre::Result<T>,
+) -> Vec<eyre::Report> {
+    if !problems.is_empty() {
+        return problems;
+    }
+    parse().err().into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lines_works() {
+        let parse = |l: &str| -> eyre::Result<usize> { Ok(l.trim().parse()?) };
+        assert_eq!(vec![1, 2, 3], parse_lines("1\n2\n\n3\n", parse).unwrap());
+
+        let error = parse_lines("1\n2\nthree\n", parse).unwrap_err();
+        assert_eq!("line 3 : 'three'", error.to_string());
+        assert_eq!("line 2, column 5", at(1, 4));
+
+        let errors: Vec<String> = check_lines("one\n2\nthree\n", parse)
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(vec!["line 1 : 'one'", "line 3 : 'three'"], errors);
+
+        let errors: Vec<String> = check_chars("#.#\n.x.\n..y", "#.")
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "unexpected 'x' at line 2, column 2",
+                "unexpected 'y' at line 3, column 3"
+            ],
+            errors
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use aoc2022::day1::InventoryStats;
+use aoc2022::solution::{Answer, Answers};
+use eyre::{eyre, Context};
+use std::fmt::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// machine readable report format, deduced from the file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Csv,
+}
+
+impl ReportFormat {
+    pub fn from_path(path: &Path) -> eyre::Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(Self::Json),
+            Some("csv") => Ok(Self::Csv),
+            _ => Err(eyre!(
+                "cannot guess report format of {}, expecting .json or .csv",
+                path.display()
+            )),
+        }
+    }
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+    match duration {
+        Some(d) => format!("{:.3}ms", d.as_secs_f64() * 1000.0),
+        None => "-".to_string(),
+    }
+}
+
+/// human readable summary, one row per day, slowest day flagged
+pub fn summary_table(days: &[(usize, Answers)]) -> String {
+    let slowest = days
+        .iter()
+        .max_by_key(|(_, a)| a.timings.total())
+        .map(|(day, _)| *day);
+
+    let mut table = format!(
+        "{:>5} {:>12} {:>12} {:>12} {:>12}\n",
+        "day", "parse", "part 1", "part 2", "total"
+    );
+    for (day, answers) in days {
+        let timings = &answers.timings;
+        let _ = writeln!(
+            table,
+            "{day:>5} {:>12} {:>12} {:>12} {:>12}{}",
+            format_duration(Some(timings.parse)),
+            format_duration(timings.part1),
+            format_duration(timings.part2),
+            format_duration(Some(timings.total())),
+            if slowest == Some(*day) {
+                " <- slowest"
+            } else {
+                ""
+            }
+        );
+    }
+    let total: Duration = days.iter().map(|(_, a)| a.timings.total()).sum();
+    let _ = write!(
+        table,
+        "{:>5} {:>12} {:>12} {:>12} {:>12}",
+        "all",
+        "",
+        "",
+        "",
+        format_duration(Some(total))
+    );
+    table
+}
+
+fn micros(duration: Option<Duration>) -> String {
+    duration
+        .map(|d| d.as_micros().to_string())
+        .unwrap_or_default()
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_answer(answer: &Option<Answer>) -> String {
+    match answer {
+        None | Some(Answer::Empty) => "null".to_string(),
+        Some(Answer::Int(v)) => v.to_string(),
+        Some(Answer::Text(t)) | Some(Answer::Screen(t)) => json_string(t),
+    }
+}
+
+fn json_micros(duration: Option<Duration>) -> String {
+    duration
+        .map(|d| d.as_micros().to_string())
+        .unwrap_or_else(|| "null".to_string())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_answer(answer: &Option<Answer>) -> String {
+    match answer {
+        None | Some(Answer::Empty) => String::new(),
+        Some(Answer::Screen(s)) => csv_field(s),
+        Some(a) => csv_field(&a.to_string()),
+    }
+}
+
+/// timings are given in microseconds
+pub fn machine_report(days: &[(usize, Answers)], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Json => {
+            let rows: Vec<String> = days
+                .iter()
+                .map(|(day, a)| {
+                    format!(
+                        "  {{\"day\": {day}, \"part1\": {}, \"part2\": {}, \"parse_us\": {}, \"part1_us\": {}, \"part2_us\": {}, \"total_us\": {}}}",
+                        json_answer(&a.part1),
+                        json_answer(&a.part2),
+                        json_micros(Some(a.timings.parse)),
+                        json_micros(a.timings.part1),
+                        json_micros(a.timings.part2),
+                        json_micros(Some(a.timings.total())),
+                    )
+                })
+                .collect();
+            format!("[\n{}\n]\n", rows.join(",\n"))
+        }
+        ReportFormat::Csv => {
+            let mut csv = "day,part1,part2,parse_us,part1_us,part2_us,total_us\n".to_string();
+            for (day, a) in days {
+                let _ = writeln!(
+                    csv,
+                    "{day},{},{},{},{},{},{}",
+                    csv_answer(&a.part1),
+                    csv_answer(&a.part2),
+                    micros(Some(a.timings.parse)),
+                    micros(a.timings.part1),
+                    micros(a.timings.part2),
+                    micros(Some(a.timings.total())),
+                );
+            }
+            csv
+        }
+    }
+}
+
+pub fn write_report(days: &[(usize, Answers)], path: &Path) -> eyre::Result<()> {
+    let report = machine_report(days, ReportFormat::from_path(path)?);
+    std::fs::write(path, report).with_context(|| format!("writing report {}", path.display()))
+}
+
+pub fn write_stats_report(stats: &InventoryStats, path: &Path) -> eyre::Result<()> {
+    if ReportFormat::from_path(path)? != ReportFormat::Json {
+        return Err(eyre!("statistics can only be written as .json"));
+    }
+    std::fs::write(path, stats.to_json())
+        .with_context(|| format!("writing statistics {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2022::solution::Timings;
+
+    #[test]
+    fn reports_work() {
+        let days = vec![
+            (
+                10,
+                Answers {
+                    part1: Some(Answer::Int(13480)),
+                    part2: Some(Answer::Screen("#.\n.#".to_string())),
+                    timings: Timings {
+                        parse: Duration::from_micros(12),
+                        part1: Some(Duration::from_micros(100)),
+                        part2: Some(Duration::from_micros(30)),
+                    },
+                },
+            ),
+            (
+                25,
+                Answers {
+                    part1: Some(Answer::Text("2=-1".to_string())),
+                    part2: None,
+                    timings: Timings {
+                        parse: Duration::from_micros(3),
+                        part1: Some(Duration::from_micros(5)),
+                        part2: None,
+                    },
+                },
+            ),
+        ];
+
+        assert_eq!(
+            "day,part1,part2,parse_us,part1_us,part2_us,total_us\n\
+             10,13480,\"#.\n.#\",12,100,30,142\n\
+             25,2=-1,,3,5,,8\n",
+            machine_report(&days, ReportFormat::Csv)
+        );
+        assert_eq!(
+            "[\n  \
+             {\"day\": 10, \"part1\": 13480, \"part2\": \"#.\\n.#\", \"parse_us\": 12, \"part1_us\": 100, \"part2_us\": 30, \"total_us\": 142},\n  \
+             {\"day\": 25, \"part1\": \"2=-1\", \"part2\": null, \"parse_us\": 3, \"part1_us\": 5, \"part2_us\": null, \"total_us\": 8}\n\
+             ]\n",
+            machine_report(&days, ReportFormat::Json)
+        );
+
+        let table = summary_table(&days);
+        assert!(table.lines().nth(1).unwrap().ends_with("<- slowest"));
+        assert!(table.lines().last().unwrap().ends_with("0.150ms"));
+
+        assert!(ReportFormat::from_path(Path::new("bench.txt")).is_err());
+    }
+}
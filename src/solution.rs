@@ -0,0 +1,187 @@
+use eyre::{eyre, WrapErr};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// which part(s) of a day puzzle should be run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parts {
+    First,
+    Second,
+    Both,
+}
+
+impl Parts {
+    pub fn first(self) -> bool {
+        matches!(self, Parts::First | Parts::Both)
+    }
+    pub fn second(self) -> bool {
+        matches!(self, Parts::Second | Parts::Both)
+    }
+}
+
+impl FromStr for Parts {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Parts::First),
+            "2" => Ok(Parts::Second),
+            "both" | "all" | "1,2" => Ok(Parts::Both),
+            _ => Err(eyre!("unknown part {s}, expecting 1, 2 or both")),
+        }
+    }
+}
+
+/// a puzzle answer, as typed on the AoC site
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+    /// CRT like drawing, one line per row, the letters being read by a human
+    Screen(String),
+    /// there is no puzzle to solve (second part of day 25)
+    Empty,
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(v) => write!(f, "{v}"),
+            Answer::Text(t) => f.write_str(t),
+            Answer::Screen(s) => write!(f, "\n{s}"),
+            Answer::Empty => f.write_str("-"),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Self::Int(value as i128)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        Self::Int(value as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+/// a day puzzle : its input model and the solvers of both its parts
+///
+/// `'i` is the lifetime of the input, that some models borrow from
+pub trait Solution<'i>: Sized {
+    fn parse(input: &'i str) -> eyre::Result<Self>;
+    fn part1(&self) -> eyre::Result<Answer>;
+    fn part2(&self) -> eyre::Result<Answer>;
+
+    /// every structural problem of `input`, without solving anything
+    ///
+    /// defaults to the error `parse` stops on, formats made of independent lines
+    /// or cells can report all of them instead
+    fn check(input: &'i str) -> Vec<eyre::Report> {
+        Self::parse(input).err().into_iter().collect()
+    }
+}
+
+/// time spent in each step of a day, a part which was not run having no timing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.part1.unwrap_or_default() + self.part2.unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+    pub timings: Timings,
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+pub fn solve<'i, S: Solution<'i>>(input: &'i str, parts: Parts) -> eyre::Result<Answers> {
+    let (solution, parse) = timed(|| S::parse(input));
+    let solution = solution.wrap_err("parsing input")?;
+    let (part1, part1_time) = parts.first().then(|| timed(|| solution.part1())).unzip();
+    let (part2, part2_time) = parts.second().then(|| timed(|| solution.part2())).unzip();
+    Ok(Answers {
+        part1: part1.transpose().wrap_err("solving part 1")?,
+        part2: part2.transpose().wrap_err("solving part 2")?,
+        timings: Timings {
+            parse,
+            part1: part1_time,
+            part2: part2_time,
+        },
+    })
+}
+
+pub fn check<'i, S: Solution<'i>>(input: &'i str) -> Vec<eyre::Report> {
+    S::check(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Words<'i> {
+        words: Vec<&'i str>,
+    }
+
+    impl<'i> Solution<'i> for Words<'i> {
+        fn parse(input: &'i str) -> eyre::Result<Self> {
+            if input.is_empty() {
+                return Err(eyre!("no words"));
+            }
+            Ok(Self {
+                words: input.split_whitespace().collect(),
+            })
+        }
+        fn part1(&self) -> eyre::Result<Answer> {
+            Ok(self.words.len().into())
+        }
+        fn part2(&self) -> eyre::Result<Answer> {
+            match self.words.len() {
+                1 => Err(eyre!("a single word")),
+                _ => Ok(self.words.concat().into()),
+            }
+        }
+    }
+
+    #[test]
+    fn solve_works() {
+        let answers = solve::<Words>("a b", Parts::Both).unwrap();
+        assert_eq!(Some(Answer::Int(2)), answers.part1);
+        assert_eq!(Some(Answer::Text("ab".to_string())), answers.part2);
+        assert!(answers.timings.part1.is_some());
+
+        let answers = solve::<Words>("a b", Parts::Second).unwrap();
+        assert_eq!(None, answers.part1);
+        assert_eq!(None, answers.timings.part1);
+        assert_eq!(Some(Answer::Text("ab".to_string())), answers.part2);
+
+        assert!(solve::<Words>("", Parts::Both).is_err());
+        assert!(solve::<Words>("a", Parts::First).is_ok());
+        assert!(solve::<Words>("a", Parts::Second).is_err());
+        assert!(check::<Words>("a").is_empty());
+        assert_eq!(1, check::<Words>("").len());
+        assert_eq!("\n#.\n.#", Answer::Screen("#.\n.#".to_string()).to_string());
+    }
+}
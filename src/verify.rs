@@ -0,0 +1,213 @@
+use aoc2022::solution::{Answer, Answers};
+use eyre::{eyre, Context};
+use std::collections::HashMap;
+use std::path::Path;
+
+pub const DEFAULT_ANSWERS_FILE: &str = "resources/answers.toml";
+
+/// expected answers by (day, part), read from a toml file such as
+/// ```toml
+/// [day10]
+/// part1 = 13480
+/// part2 = """
+/// ####  ##
+/// #    #  #
+/// """
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ExpectedAnswers {
+    answers: HashMap<(usize, usize), String>,
+}
+
+impl ExpectedAnswers {
+    pub fn parse(content: &str) -> eyre::Result<Self> {
+        let table: toml::value::Table = toml::from_str(content)?;
+        let mut answers = HashMap::new();
+        for (section, parts) in table {
+            let day: usize = section
+                .strip_prefix("day")
+                .and_then(|d| d.parse().ok())
+                .ok_or_else(|| eyre!("[{section}] is not a [dayN] section"))?;
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| eyre!("{section} is not a section"))?;
+            for (key, value) in parts {
+                let part: usize = match key.as_str() {
+                    "part1" => 1,
+                    "part2" => 2,
+                    _ => return Err(eyre!("unknown key {key} in [{section}]")),
+                };
+                let expected = match value {
+                    toml::Value::Integer(v) => v.to_string(),
+                    toml::Value::String(s) => s.clone(),
+                    _ => return Err(eyre!("{section}.{key} should be an integer or a string")),
+                };
+                answers.insert((day, part), expected);
+            }
+        }
+        Ok(Self { answers })
+    }
+
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("reading answers {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("parsing answers {}", path.display()))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing { actual: String },
+}
+
+/// screens are compared without trailing spaces, that editors tend to strip
+fn normalize(answer: &str) -> String {
+    answer
+        .trim_matches('\n')
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn raw_answer(answer: &Answer) -> Option<String> {
+    match answer {
+        Answer::Int(v) => Some(v.to_string()),
+        Answer::Text(t) | Answer::Screen(t) => Some(t.clone()),
+        Answer::Empty => None,
+    }
+}
+
+/// one verdict per solved (day, part), parts without any answer to give being skipped
+pub fn verify(
+    days: &[(usize, Answers)],
+    expected: &ExpectedAnswers,
+) -> Vec<(usize, usize, Verdict)> {
+    let mut verdicts = vec![];
+    for (day, answers) in days {
+        for (part, answer) in [(1, &answers.part1), (2, &answers.part2)] {
+            let Some(actual) = answer.as_ref().and_then(raw_answer) else {
+                continue;
+            };
+            let verdict = match expected.answers.get(&(*day, part)) {
+                None => Verdict::Missing { actual },
+                Some(e) if normalize(e) == normalize(&actual) => Verdict::Pass,
+                Some(e) => Verdict::Fail {
+                    expected: e.clone(),
+                    actual,
+                },
+            };
+            verdicts.push((*day, part, verdict));
+        }
+    }
+    verdicts
+}
+
+/// prints every verdict, failing when any answer does not match
+pub fn print_verdicts(verdicts: &[(usize, usize, Verdict)]) -> eyre::Result<()> {
+    let mut failed = 0;
+    let mut missing = 0;
+    for (day, part, verdict) in verdicts {
+        match verdict {
+            Verdict::Pass => println!("day {day:>2} part {part} : ok"),
+            Verdict::Fail { expected, actual } => {
+                failed += 1;
+                println!("day {day:>2} part {part} : FAILED, expected {expected} got {actual}");
+            }
+            Verdict::Missing { actual } => {
+                missing += 1;
+                println!("day {day:>2} part {part} : missing, got {actual}");
+            }
+        }
+    }
+    println!(
+        "\n{} passed, {failed} failed, {missing} missing",
+        verdicts.len() - failed - missing
+    );
+    if failed > 0 {
+        return Err(eyre!("{failed} answer(s) do not match"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn verify_works() {
+        let expected = ExpectedAnswers::parse(indoc! {r#"
+            [day1]
+            part1 = 24000
+            part2 = 45000
+
+            [day10]
+            part2 = """
+            #.
+            .#
+            """
+
+            [day25]
+            part1 = "2=-1=0"
+        "#})
+        .unwrap();
+
+        let days = vec![
+            (
+                1,
+                Answers {
+                    part1: Some(Answer::Int(24000)),
+                    part2: Some(Answer::Int(45001)),
+                    ..Default::default()
+                },
+            ),
+            (
+                10,
+                Answers {
+                    part1: Some(Answer::Int(13140)),
+                    part2: Some(Answer::Screen("#. \n.# ".to_string())),
+                    ..Default::default()
+                },
+            ),
+            (
+                25,
+                Answers {
+                    part1: Some(Answer::Text("2=-1=0".to_string())),
+                    part2: Some(Answer::Empty),
+                    ..Default::default()
+                },
+            ),
+        ];
+
+        assert_eq!(
+            vec![
+                (1, 1, Verdict::Pass),
+                (
+                    1,
+                    2,
+                    Verdict::Fail {
+                        expected: "45000".to_string(),
+                        actual: "45001".to_string()
+                    }
+                ),
+                (
+                    10,
+                    1,
+                    Verdict::Missing {
+                        actual: "13140".to_string()
+                    }
+                ),
+                (10, 2, Verdict::Pass),
+                (25, 1, Verdict::Pass),
+            ],
+            verify(&days, &expected)
+        );
+
+        assert!(ExpectedAnswers::parse("[day1]\npart3 = 1").is_err());
+        assert!(ExpectedAnswers::parse("[one]\npart1 = 1").is_err());
+        assert!(ExpectedAnswers::parse("[day1]\npart1 = 1.5").is_err());
+    }
+}